@@ -0,0 +1,157 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use mycrate::generator::config::TransferGenConfig;
+use mycrate::generator::transfer::{DefaultTransferGenerator, TransferGenerator};
+use mycrate::model::{Address, Amount, Transfer};
+use mycrate::pipeline::window_max;
+
+const WHALE: Address = Address::from_bytes([0xee; 20]);
+const SIZES: [usize; 3] = [1_000, 5_000, 20_000];
+
+/// Generated transfers where every transfer either pays or is paid by one
+/// address, so its balance history is as long as the input.
+fn whale_transfers(count: usize) -> Result<Vec<Transfer>> {
+    let generator = DefaultTransferGenerator::new(TransferGenConfig::default())?;
+    let mut transfers = generator.generate(count)?;
+
+    for (i, t) in transfers.iter_mut().enumerate() {
+        if i % 2 == 0 {
+            t.to = WHALE;
+        } else {
+            t.from = WHALE;
+        }
+    }
+    Ok(transfers)
+}
+
+/// Balance of `address` after each of its transfers, in timestamp order.
+fn history(transfers: &[Transfer], address: Address) -> Vec<(u64, Amount)> {
+    let mut sorted = transfers.to_vec();
+    sorted.sort_by_key(|t| t.ts);
+
+    let mut balance = Amount::ZERO;
+    let mut history = Vec::new();
+    for t in &sorted {
+        for (side, delta) in [(t.from, -t.amount), (t.to, t.amount)] {
+            if side == address {
+                balance += delta;
+                history.push((t.ts, balance));
+            }
+        }
+    }
+    history
+}
+
+/// The same history in the representation the legacy loop took.
+fn legacy_history(history: &[(u64, Amount)]) -> Result<Vec<(DateTime<Utc>, f64)>> {
+    history
+        .iter()
+        .map(|&(ts, balance)| {
+            let dt = DateTime::<Utc>::from_timestamp(ts as i64, 0)
+                .with_context(|| format!("Invalid timestamp: {}", ts))?;
+            Ok((dt, balance.to_f64()))
+        })
+        .collect()
+}
+
+/// The nested-loop `calculate_max_balance_for_period` the pipeline used
+/// before `StatsEngine`. It finds the largest balance within any period
+/// rather than the trailing one, so only its cost is comparable.
+mod legacy {
+    use super::*;
+
+    pub fn max_balance_for_period(
+        history: &[(DateTime<Utc>, f64)],
+        period: Duration,
+    ) -> Result<f64> {
+        let mut max_balance = 0.0;
+
+        for (i, &(ts, balance)) in history.iter().enumerate() {
+            let window_end = ts
+                .checked_add_signed(period)
+                .context("DateTime arithmetic overflow")?;
+
+            let mut current_max = balance;
+            for &(next_ts, next_balance) in &history[i + 1..] {
+                if next_ts > window_end {
+                    break;
+                }
+                if next_balance > current_max {
+                    current_max = next_balance;
+                }
+            }
+
+            if current_max > max_balance {
+                max_balance = current_max;
+            }
+        }
+
+        Ok(max_balance)
+    }
+}
+
+fn bench_window_max(c: &mut Criterion) {
+    let mut group = c.benchmark_group("whale_window_max");
+    group.sample_size(10);
+
+    let periods = [("1h", 3_600), ("24h", 86_400), ("7d", 7 * 86_400)];
+    for size in SIZES {
+        let history = whale_transfers(size).map(|t| history(&t, WHALE));
+        let Ok((history, legacy_history)) =
+            history.and_then(|h| legacy_history(&h).map(|legacy| (h, legacy)))
+        else {
+            eprintln!("Failed to generate {} transfers", size);
+            continue;
+        };
+
+        let end = history.last().map_or(0, |&(ts, _)| ts);
+        for (label, secs) in periods {
+            let period = Duration::seconds(secs as i64);
+            group.bench_with_input(
+                BenchmarkId::new(format!("nested_loop_{}", label), size),
+                &legacy_history,
+                |b, h| b.iter(|| legacy::max_balance_for_period(black_box(h), period)),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("deque_{}", label), size),
+                &history,
+                |b, h| b.iter(|| window_max(black_box(h), secs, end)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+#[cfg(feature = "parallel")]
+fn bench_parallel(c: &mut Criterion) {
+    use mycrate::pipeline::{calculate_user_stats_with, PipelineConfig};
+
+    let mut group = c.benchmark_group("parallel_stats");
+    group.sample_size(10);
+
+    let Ok(transfers) = whale_transfers(100_000) else {
+        eprintln!("Failed to generate transfers");
+        return;
+    };
+
+    for threads in [1, 2, 4, 0] {
+        let config = PipelineConfig {
+            threads,
+            ..PipelineConfig::default()
+        };
+        group.bench_with_input(BenchmarkId::new("threads", threads), &transfers, |b, t| {
+            b.iter(|| calculate_user_stats_with(black_box(t), &config))
+        });
+    }
+
+    group.finish();
+}
+
+#[cfg(feature = "parallel")]
+criterion_group!(benches, bench_window_max, bench_parallel);
+#[cfg(not(feature = "parallel"))]
+criterion_group!(benches, bench_window_max);
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+CREATE TABLE transfers (
+    key UInt128,
+    ts UInt64,
+    `from` FixedString(20),
+    `to` FixedString(20),
+    amount Decimal128(18),
+    usd_price Float64
+) ENGINE = ReplacingMergeTree() ORDER BY (ts, `from`, `to`, key)
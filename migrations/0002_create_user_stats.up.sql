@@ -0,0 +1,9 @@
+CREATE TABLE user_stats (
+    address FixedString(20),
+    total_volume Decimal128(18),
+    avg_buy_price Float64,
+    avg_sell_price Float64,
+    max_balance Decimal128(18),
+    max_balance_windows Map(String, Decimal128(18)),
+    version UInt64
+) ENGINE = ReplacingMergeTree(version) ORDER BY address
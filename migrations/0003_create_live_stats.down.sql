@@ -0,0 +1,2 @@
+DROP VIEW IF EXISTS user_stats_mv;
+DROP TABLE IF EXISTS user_stats_agg
@@ -0,0 +1,33 @@
+CREATE TABLE user_stats_agg (
+    address FixedString(20),
+    total_volume AggregateFunction(sum, Decimal128(18)),
+    buy_amount AggregateFunction(sum, Decimal128(18)),
+    buy_value AggregateFunction(sum, Float64),
+    sell_amount AggregateFunction(sum, Decimal128(18)),
+    sell_value AggregateFunction(sum, Float64)
+) ENGINE = AggregatingMergeTree ORDER BY address;
+
+CREATE MATERIALIZED VIEW user_stats_mv TO user_stats_agg AS
+SELECT
+    side.1 AS address,
+    sumState(amount) AS total_volume,
+    sumState(if(side.2, amount, toDecimal128(0, 18))) AS buy_amount,
+    sumState(if(side.2, usd_price * toFloat64(amount), 0)) AS buy_value,
+    sumState(if(side.2, toDecimal128(0, 18), amount)) AS sell_amount,
+    sumState(if(side.2, 0, usd_price * toFloat64(amount))) AS sell_value
+FROM transfers
+ARRAY JOIN [(`from`, 0), (`to`, 1)] AS side
+GROUP BY address;
+
+-- Same aggregation as user_stats_mv, over the transfers stored before it.
+INSERT INTO user_stats_agg
+SELECT
+    side.1 AS address,
+    sumState(amount) AS total_volume,
+    sumState(if(side.2, amount, toDecimal128(0, 18))) AS buy_amount,
+    sumState(if(side.2, usd_price * toFloat64(amount), 0)) AS buy_value,
+    sumState(if(side.2, toDecimal128(0, 18), amount)) AS sell_amount,
+    sumState(if(side.2, 0, usd_price * toFloat64(amount))) AS sell_value
+FROM transfers FINAL
+ARRAY JOIN [(`from`, 0), (`to`, 1)] AS side
+GROUP BY address
@@ -0,0 +1,665 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+
+use mycrate::export::{ExportFormat, ExportOptions, SortOrder, StatsColumn};
+use mycrate::generator::config::TransferGenConfig;
+use mycrate::generator::ledger::LedgerConfig;
+use mycrate::generator::pool::AddressPoolConfig;
+use mycrate::generator::price::PriceProcessConfig;
+use mycrate::generator::timing::{Arrival, BurstConfig, Seasonality, TimePatternConfig};
+use mycrate::import::{ColumnMapping, ImportOptions, TimestampFormat};
+use mycrate::model::Address;
+use mycrate::pipeline::{BalanceWindow, PipelineConfig};
+use mycrate::storage::{InsertConfig, SaveMode};
+
+use crate::cli::config::{ConfigFile, StorageFile};
+
+#[derive(Debug, Parser)]
+#[command(name = "token_transfers", version, about = "Token transfer analytics service")]
+pub struct Cli {
+    /// TOML or YAML file with generator, pipeline and storage settings
+    #[arg(short, long, global = true, env = "TOKEN_TRANSFERS_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Generate fake transfers and write them as JSON Lines
+    Generate(GenerateArgs),
+    /// Load transfers from a JSON Lines or CSV file into storage
+    Ingest(IngestArgs),
+    /// Stream generated transfers straight into storage
+    Load(LoadArgs),
+    /// Calculate user statistics from stored transfers and save them
+    Analyze(AnalyzeArgs),
+    /// Print or export saved user statistics
+    Stats(StatsArgs),
+    /// Generate, ingest and analyze in one go
+    Run(RunArgs),
+    /// Apply or revert ClickHouse schema migrations
+    Migrate(MigrateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    #[command(flatten)]
+    pub generator: GeneratorArgs,
+
+    /// Output file, stdout when omitted
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct IngestArgs {
+    /// JSON Lines, CSV or eth_getLogs JSON file, stdin when omitted
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+
+    /// Input format, guessed from the file extension when omitted
+    #[arg(long, value_enum)]
+    pub format: Option<InputFormat>,
+
+    /// Timestamp encoding: auto, secs, millis or iso8601
+    #[arg(long, default_value = "auto")]
+    pub ts_format: TimestampFormat,
+
+    #[arg(long, default_value = "ts")]
+    pub ts_column: String,
+
+    #[arg(long, default_value = "from")]
+    pub from_column: String,
+
+    #[arg(long, default_value = "to")]
+    pub to_column: String,
+
+    #[arg(long, default_value = "amount")]
+    pub amount_column: String,
+
+    #[arg(long, default_value = "usd_price")]
+    pub price_column: String,
+
+    /// CSV field delimiter
+    #[arg(long, default_value_t = ',')]
+    pub delimiter: char,
+
+    /// Skip up to this many invalid rows; by default any invalid row aborts
+    #[arg(long, default_value_t = 0)]
+    pub max_errors: usize,
+
+    /// Only read and report the input, without saving it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub erc20: Erc20Args,
+
+    #[command(flatten)]
+    pub storage: StorageArgs,
+}
+
+/// Decoding of `erc20-logs` input.
+#[derive(Debug, Args)]
+pub struct Erc20Args {
+    /// Token decimals
+    #[arg(long, default_value_t = 18)]
+    pub decimals: u8,
+
+    /// Keep only logs emitted by this token contract
+    #[arg(long)]
+    pub token: Option<Address>,
+
+    /// eth_getBlockByNumber responses with the timestamps of the logged blocks
+    #[arg(long)]
+    pub blocks: Option<PathBuf>,
+
+    /// USD price assigned to every decoded transfer
+    #[arg(long)]
+    pub usd_price: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    Jsonl,
+    Csv,
+    /// ERC-20 Transfer logs from eth_getLogs
+    Erc20Logs,
+}
+
+impl IngestArgs {
+    pub fn format(&self) -> InputFormat {
+        let extension = self.input.as_ref().and_then(|p| p.extension()).and_then(|e| e.to_str());
+
+        self.format.unwrap_or(match extension {
+            Some("csv" | "tsv") => InputFormat::Csv,
+            _ => InputFormat::Jsonl,
+        })
+    }
+
+    pub fn options(&self) -> Result<ImportOptions> {
+        let Ok(delimiter) = u8::try_from(self.delimiter) else {
+            bail!("Delimiter must be an ASCII character, got '{}'", self.delimiter);
+        };
+
+        Ok(ImportOptions {
+            columns: ColumnMapping {
+                ts: self.ts_column.clone(),
+                from: self.from_column.clone(),
+                to: self.to_column.clone(),
+                amount: self.amount_column.clone(),
+                usd_price: self.price_column.clone(),
+            },
+            timestamp: self.ts_format,
+            delimiter,
+            max_errors: Some(self.max_errors),
+        })
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct LoadArgs {
+    #[command(flatten)]
+    pub generator: GeneratorArgs,
+
+    /// Transfers generated and inserted per chunk
+    #[arg(long, env = "TRANSFERS_CHUNK_SIZE", default_value_t = 100_000)]
+    pub chunk_size: usize,
+
+    #[command(flatten)]
+    pub storage: StorageArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    /// Schema version to migrate up or down to, 0 reverts everything [default: latest]
+    #[arg(long)]
+    pub to: Option<u32>,
+
+    /// Print applied and pending migrations without changing anything
+    #[arg(long, conflicts_with = "to")]
+    pub status: bool,
+
+    #[command(flatten)]
+    pub storage: StorageArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct AnalyzeArgs {
+    #[command(flatten)]
+    pub pipeline: PipelineArgs,
+
+    /// Where statistics are calculated; clickhouse runs the calculation as SQL
+    #[arg(long, env = "STATS_BACKEND", value_enum, default_value_t = StatsBackendKind::Process)]
+    pub stats_backend: StatsBackendKind,
+
+    #[command(flatten)]
+    pub storage: StorageArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct StatsArgs {
+    #[command(subcommand)]
+    pub action: Option<StatsCommand>,
+
+    /// Print only the top N addresses by total volume
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+
+    #[command(flatten)]
+    pub storage: StorageArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StatsCommand {
+    /// Write saved user statistics to a CSV, JSON, NDJSON or Parquet file
+    Export(ExportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Output file, stdout when omitted
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// csv, json, ndjson or parquet (needs the `parquet` feature); guessed from the output extension, csv otherwise
+    #[arg(long)]
+    pub format: Option<ExportFormat>,
+
+    /// Columns to write, e.g. `address,total_volume,max_balance_24h`; all when omitted
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<StatsColumn>,
+
+    /// Column to sort by
+    #[arg(long, default_value = "total_volume")]
+    pub sort: StatsColumn,
+
+    /// Sort ascending instead of descending
+    #[arg(long)]
+    pub asc: bool,
+
+    /// Write only the first N rows after sorting
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+
+    #[command(flatten)]
+    pub storage: StorageArgs,
+}
+
+impl ExportArgs {
+    pub fn format(&self) -> ExportFormat {
+        self.format
+            .or_else(|| self.output.as_deref().and_then(ExportFormat::from_path))
+            .unwrap_or(ExportFormat::Csv)
+    }
+
+    pub fn options(&self) -> ExportOptions {
+        ExportOptions {
+            columns: self.columns.clone(),
+            sort: SortOrder {
+                column: self.sort.clone(),
+                descending: !self.asc,
+            },
+            limit: self.limit,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub generator: GeneratorArgs,
+
+    #[command(flatten)]
+    pub pipeline: PipelineArgs,
+
+    /// Also write the saved statistics to this file, format taken from the extension
+    #[arg(long)]
+    pub export: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub storage: StorageArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct GeneratorArgs {
+    /// Number of transfers to generate [default: 10000]
+    #[arg(short = 'n', long, env = "TRANSFERS_COUNT")]
+    pub count: Option<usize>,
+
+    /// Lower bound of the token amount
+    #[arg(long, env = "TRANSFERS_MIN_AMOUNT")]
+    pub min_amount: Option<f64>,
+
+    /// Upper bound of the token amount (exclusive)
+    #[arg(long, env = "TRANSFERS_MAX_AMOUNT")]
+    pub max_amount: Option<f64>,
+
+    /// Lower bound of the USD price
+    #[arg(long, env = "TRANSFERS_MIN_PRICE")]
+    pub min_price: Option<f64>,
+
+    /// Upper bound of the USD price (exclusive)
+    #[arg(long, env = "TRANSFERS_MAX_PRICE")]
+    pub max_price: Option<f64>,
+
+    /// Maximum transfer age in seconds relative to now
+    #[arg(long, env = "TRANSFERS_MAX_AGE_SECS")]
+    pub max_age_secs: Option<u64>,
+
+    /// Seed for reproducible output
+    #[arg(long, env = "TRANSFERS_SEED")]
+    pub seed: Option<u64>,
+
+    /// Unix timestamp to generate relative to instead of the current time
+    #[arg(long, env = "TRANSFERS_NOW")]
+    pub now: Option<u64>,
+
+    /// Draw addresses from a pool of this many regular addresses
+    #[arg(long, env = "TRANSFERS_POOL_SIZE")]
+    pub pool_size: Option<usize>,
+
+    /// Zipf exponent of address popularity in the pool
+    #[arg(long, env = "TRANSFERS_ZIPF_EXPONENT")]
+    pub zipf_exponent: Option<f64>,
+
+    /// Number of exchange addresses in the pool
+    #[arg(long, env = "TRANSFERS_EXCHANGES")]
+    pub exchanges: Option<usize>,
+
+    /// Number of whale addresses in the pool
+    #[arg(long, env = "TRANSFERS_WHALES")]
+    pub whales: Option<usize>,
+
+    /// Keep a ledger so no address spends more than it holds
+    #[arg(long, env = "TRANSFERS_LEDGER")]
+    pub ledger: bool,
+
+    /// Sender of mint transfers in ledger mode
+    #[arg(long, env = "TRANSFERS_GENESIS_ADDRESS")]
+    pub genesis_address: Option<Address>,
+
+    /// Probability of a mint transfer in ledger mode
+    #[arg(long, env = "TRANSFERS_MINT_SHARE")]
+    pub mint_share: Option<f64>,
+
+    /// Simulate prices with geometric Brownian motion instead of uniform draws
+    #[arg(long, env = "TRANSFERS_GBM")]
+    pub gbm: bool,
+
+    /// Starting mid price of the simulated series
+    #[arg(long, env = "TRANSFERS_PRICE_INITIAL")]
+    pub price_initial: Option<f64>,
+
+    /// Annualised drift of the simulated series
+    #[arg(long, env = "TRANSFERS_PRICE_DRIFT", allow_negative_numbers = true)]
+    pub price_drift: Option<f64>,
+
+    /// Annualised volatility of the simulated series
+    #[arg(long, env = "TRANSFERS_PRICE_VOLATILITY")]
+    pub price_volatility: Option<f64>,
+
+    /// Expected price jumps per year
+    #[arg(long, env = "TRANSFERS_PRICE_JUMP_INTENSITY")]
+    pub price_jump_intensity: Option<f64>,
+
+    /// Mean log size of a price jump
+    #[arg(long, env = "TRANSFERS_PRICE_JUMP_MEAN", allow_negative_numbers = true)]
+    pub price_jump_mean: Option<f64>,
+
+    /// Standard deviation of the log size of a price jump
+    #[arg(long, env = "TRANSFERS_PRICE_JUMP_STD")]
+    pub price_jump_std: Option<f64>,
+
+    /// Relative half-spread applied around the mid price
+    #[arg(long, env = "TRANSFERS_PRICE_SPREAD")]
+    pub price_spread: Option<f64>,
+
+    /// Unix timestamp of the range start, overrides `--max-age-secs`
+    #[arg(long, env = "TRANSFERS_START_TS")]
+    pub start_ts: Option<u64>,
+
+    /// Unix timestamp of the range end, overrides `--now`
+    #[arg(long, env = "TRANSFERS_END_TS")]
+    pub end_ts: Option<u64>,
+
+    /// Emit transfers ordered by timestamp
+    #[arg(long, env = "TRANSFERS_SORTED")]
+    pub sorted: bool,
+
+    /// Poisson arrivals at this many transfers per second from the range start to its end
+    #[arg(long, env = "TRANSFERS_POISSON_RATE")]
+    pub poisson_rate: Option<f64>,
+
+    /// Number of activity bursts placed in the range
+    #[arg(long, env = "TRANSFERS_BURSTS")]
+    pub bursts: Option<usize>,
+
+    /// Length of each burst in seconds [default: 3600]
+    #[arg(long, env = "TRANSFERS_BURST_DURATION_SECS")]
+    pub burst_duration_secs: Option<u64>,
+
+    /// Activity multiplier inside a burst [default: 10]
+    #[arg(long, env = "TRANSFERS_BURST_INTENSITY")]
+    pub burst_intensity: Option<f64>,
+
+    /// Amplitude of the daily activity cycle in [0, 1)
+    #[arg(long, env = "TRANSFERS_DAILY_AMPLITUDE")]
+    pub daily_amplitude: Option<f64>,
+
+    /// Amplitude of the weekday/weekend cycle in [0, 1)
+    #[arg(long, env = "TRANSFERS_WEEKLY_AMPLITUDE")]
+    pub weekly_amplitude: Option<f64>,
+
+    /// UTC hour of peak daily activity [default: 15]
+    #[arg(long, env = "TRANSFERS_PEAK_HOUR")]
+    pub peak_hour: Option<u32>,
+}
+
+impl GeneratorArgs {
+    pub fn count(&self, file: &ConfigFile) -> usize {
+        self.count.or(file.count).unwrap_or(10_000)
+    }
+
+    /// Generator settings with flags and env vars layered over `file`.
+    pub fn config(&self, file: &ConfigFile) -> Result<TransferGenConfig> {
+        let base = file.generator.clone();
+
+        Ok(TransferGenConfig {
+            min_amount: self.min_amount.unwrap_or(base.min_amount),
+            max_amount: self.max_amount.unwrap_or(base.max_amount),
+            min_price: self.min_price.unwrap_or(base.min_price),
+            max_price: self.max_price.unwrap_or(base.max_price),
+            max_age_secs: self.max_age_secs.unwrap_or(base.max_age_secs),
+            seed: self.seed.or(base.seed),
+            now: self.now.or(base.now),
+            pool: self.pool_config(base.pool)?,
+            price_process: self.price_process(base.price_process)?,
+            start_ts: self.start_ts.or(base.start_ts),
+            end_ts: self.end_ts.or(base.end_ts),
+            time_pattern: self.time_pattern(base.time_pattern),
+            sorted: self.sorted || base.sorted,
+        })
+    }
+
+    fn time_pattern(&self, base: TimePatternConfig) -> TimePatternConfig {
+        let bursts = match (self.bursts, base.bursts) {
+            (Some(count), base) => Some(BurstConfig {
+                count,
+                ..base.unwrap_or_default()
+            }),
+            (None, base) => base,
+        };
+        let seasonal = self.daily_amplitude.is_some()
+            || self.weekly_amplitude.is_some()
+            || self.peak_hour.is_some();
+        let seasonality = match base.seasonality {
+            None if !seasonal => None,
+            base => Some(base.unwrap_or_default()),
+        };
+
+        TimePatternConfig {
+            arrival: self
+                .poisson_rate
+                .map_or(base.arrival, |rate| Arrival::Poisson { rate }),
+            bursts: bursts.map(|b| BurstConfig {
+                duration_secs: self.burst_duration_secs.unwrap_or(b.duration_secs),
+                intensity: self.burst_intensity.unwrap_or(b.intensity),
+                ..b
+            }),
+            seasonality: seasonality.map(|s| Seasonality {
+                daily_amplitude: self.daily_amplitude.unwrap_or(s.daily_amplitude),
+                peak_hour: self.peak_hour.unwrap_or(s.peak_hour),
+                weekly_amplitude: self.weekly_amplitude.unwrap_or(s.weekly_amplitude),
+            }),
+        }
+    }
+
+    fn price_process(&self, base: Option<PriceProcessConfig>) -> Result<Option<PriceProcessConfig>> {
+        let overrides = [
+            self.price_initial,
+            self.price_drift,
+            self.price_volatility,
+            self.price_jump_intensity,
+            self.price_jump_mean,
+            self.price_jump_std,
+            self.price_spread,
+        ];
+        let base = match base {
+            None if self.gbm => PriceProcessConfig::default(),
+            None if overrides.iter().any(Option::is_some) => {
+                bail!("Price process flags need --gbm or a price_process section in the config")
+            }
+            None => return Ok(None),
+            Some(base) => base,
+        };
+
+        Ok(Some(PriceProcessConfig {
+            initial_price: self.price_initial.unwrap_or(base.initial_price),
+            drift: self.price_drift.unwrap_or(base.drift),
+            volatility: self.price_volatility.unwrap_or(base.volatility),
+            jump_intensity: self.price_jump_intensity.unwrap_or(base.jump_intensity),
+            jump_mean: self.price_jump_mean.unwrap_or(base.jump_mean),
+            jump_std: self.price_jump_std.unwrap_or(base.jump_std),
+            spread: self.price_spread.unwrap_or(base.spread),
+        }))
+    }
+
+    pub fn ledger_config(&self, file: &ConfigFile) -> Result<Option<LedgerConfig>> {
+        let base = match file.ledger.clone() {
+            None if self.ledger => LedgerConfig::default(),
+            None if self.genesis_address.is_some() || self.mint_share.is_some() => {
+                bail!("Ledger flags need --ledger or a ledger section in the config")
+            }
+            None => return Ok(None),
+            Some(base) => base,
+        };
+
+        Ok(Some(LedgerConfig {
+            genesis_address: self.genesis_address.unwrap_or(base.genesis_address),
+            mint_share: self.mint_share.unwrap_or(base.mint_share),
+        }))
+    }
+
+    fn pool_config(&self, base: Option<AddressPoolConfig>) -> Result<Option<AddressPoolConfig>> {
+        let base = match (self.pool_size, base) {
+            (Some(size), base) => AddressPoolConfig {
+                size,
+                ..base.unwrap_or_default()
+            },
+            (None, Some(base)) => base,
+            (None, None) => {
+                if self.zipf_exponent.is_some() || self.exchanges.is_some() || self.whales.is_some() {
+                    bail!("Address pool flags need --pool-size or a pool section in the config");
+                }
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(AddressPoolConfig {
+            zipf_exponent: self.zipf_exponent.unwrap_or(base.zipf_exponent),
+            exchanges: self.exchanges.unwrap_or(base.exchanges),
+            whales: self.whales.unwrap_or(base.whales),
+            ..base
+        }))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct PipelineArgs {
+    /// Trailing windows for max balance, e.g. `5m,1h,30d`
+    #[arg(long = "window", env = "STATS_WINDOWS", value_delimiter = ',')]
+    pub windows: Option<Vec<BalanceWindow>>,
+
+    /// Worker threads for stats calculation, 0 for one per core (needs the `parallel` feature) [default: 1]
+    #[arg(long, env = "STATS_THREADS")]
+    pub threads: Option<usize>,
+}
+
+impl PipelineArgs {
+    /// Pipeline settings with flags and env vars layered over `file`.
+    pub fn config(&self, file: Option<&PipelineConfig>) -> Result<PipelineConfig> {
+        let base = file.cloned().unwrap_or_default();
+
+        let config = PipelineConfig {
+            windows: self.windows.clone().unwrap_or(base.windows),
+            threads: self.threads.unwrap_or(base.threads),
+            ..base
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsBackendKind {
+    /// Load transfers and calculate in this process
+    Process,
+    /// Calculate inside ClickHouse with INSERT ... SELECT
+    Clickhouse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    Clickhouse,
+    Memory,
+}
+
+#[derive(Debug, Args)]
+pub struct StorageArgs {
+    /// Where transfers and statistics are persisted [default: clickhouse]
+    #[arg(long, env = "STORAGE_BACKEND", value_enum)]
+    pub backend: Option<StorageBackend>,
+
+    /// ClickHouse HTTP endpoint [default: http://clickhouse:8123]
+    #[arg(long, env = "CLICKHOUSE_URL")]
+    pub storage_url: Option<String>,
+
+    #[arg(long, env = "CLICKHOUSE_USER")]
+    pub storage_user: Option<String>,
+
+    #[arg(long, env = "CLICKHOUSE_PASSWORD", hide_env_values = true)]
+    pub storage_password: Option<String>,
+
+    #[arg(long, env = "CLICKHOUSE_DB")]
+    pub storage_database: Option<String>,
+
+    /// replace drops stored rows first, append keeps them and skips duplicates [default: replace]
+    #[arg(long, env = "STORAGE_SAVE_MODE")]
+    pub save_mode: Option<SaveMode>,
+
+    /// Rows per ClickHouse INSERT request [default: 100000]
+    #[arg(long, env = "CLICKHOUSE_INSERT_CHUNK_SIZE")]
+    pub insert_chunk_size: Option<usize>,
+
+    /// Retries of an INSERT chunk after a transient error [default: 5]
+    #[arg(long, env = "CLICKHOUSE_INSERT_RETRIES")]
+    pub insert_retries: Option<u32>,
+
+    /// Keep volume and prices up to date in ClickHouse with a materialized view
+    #[arg(long, env = "CLICKHOUSE_LIVE_STATS")]
+    pub live_stats: bool,
+}
+
+/// Storage connection resolved from [`StorageArgs`] and the config file.
+#[derive(Debug)]
+pub struct StorageSettings {
+    pub backend: StorageBackend,
+    pub url: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+    pub mode: SaveMode,
+    pub insert: InsertConfig,
+    pub live_stats: bool,
+}
+
+impl StorageArgs {
+    pub fn settings(&self, file: &StorageFile) -> StorageSettings {
+        StorageSettings {
+            backend: self.backend.or(file.backend).unwrap_or(StorageBackend::Clickhouse),
+            url: self
+                .storage_url
+                .clone()
+                .or_else(|| file.url.clone())
+                .unwrap_or_else(|| "http://clickhouse:8123".to_string()),
+            user: self.storage_user.clone().or_else(|| file.user.clone()),
+            password: self.storage_password.clone().or_else(|| file.password.clone()),
+            database: self.storage_database.clone().or_else(|| file.database.clone()),
+            mode: self.save_mode.or(file.save_mode).unwrap_or_default(),
+            insert: InsertConfig {
+                chunk_size: self.insert_chunk_size.unwrap_or(file.insert.chunk_size),
+                max_retries: self.insert_retries.unwrap_or(file.insert.max_retries),
+                ..file.insert.clone()
+            },
+            live_stats: self.live_stats || file.live_stats,
+        }
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use clickhouse::Client;
+
+use mycrate::export::{self, ExportFormat, ExportOptions};
+use mycrate::generator::ledger::LedgerTransferGenerator;
+use mycrate::generator::transfer::{DefaultTransferGenerator, TransferGenerator};
+use mycrate::import::erc20::{self, Erc20Config, FixedPrice};
+use mycrate::import::{read_csv, read_ndjson, ImportOptions, ImportReport};
+use mycrate::model::{Transfer, UserStats};
+use mycrate::pipeline::{
+    calculate_user_stats_with, BalanceWindow, InProcessStatsBackend, PipelineConfig, StatsBackend,
+};
+use mycrate::storage::migrations::{self, MIGRATIONS};
+use mycrate::storage::{
+    ChunkedInsert, ClickHouseStatsBackend, ClickHouseStorage, InMemoryStorage, InsertProgress,
+    InsertSummary, Migrator, SaveMode, Storage,
+};
+
+use crate::cli::args::{
+    AnalyzeArgs, Erc20Args, ExportArgs, GenerateArgs, GeneratorArgs, IngestArgs, InputFormat, LoadArgs,
+    MigrateArgs, RunArgs, StatsArgs, StatsBackendKind, StatsCommand, StorageArgs, StorageBackend,
+    StorageSettings,
+};
+use crate::cli::config::ConfigFile;
+
+pub async fn generate(args: &GenerateArgs, config: &ConfigFile) -> Result<()> {
+    let transfers = generate_test_data(&args.generator, config)?;
+
+    match &args.output {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            write_transfers(BufWriter::new(file), &transfers)?;
+        }
+        None => write_transfers(io::stdout().lock(), &transfers)?,
+    }
+
+    Ok(())
+}
+
+pub async fn ingest(args: &IngestArgs, config: &ConfigFile) -> Result<()> {
+    if !args.dry_run {
+        require_persistent_storage("ingest", &args.storage, config)?;
+    }
+    let options = args.options()?;
+    let report = match &args.input {
+        Some(path) => {
+            let file = File::open(path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            import_transfers(BufReader::new(file), args, &options)?
+        }
+        None => import_transfers(io::stdin().lock(), args, &options)?,
+    };
+
+    for error in &report.errors {
+        eprintln!("Пропущена строка: {}", error);
+    }
+    eprintln!(
+        "Прочитано {} трансферов, пропущено строк: {}",
+        report.transfers.len(),
+        report.errors.len()
+    );
+    if args.dry_run {
+        return Ok(());
+    }
+
+    let storage = initialize_storage(&args.storage, config).await?;
+    save_transfers(&storage, &report.transfers).await
+}
+
+pub async fn load(args: &LoadArgs, config: &ConfigFile) -> Result<()> {
+    if args.generator.ledger_config(config)?.is_some() {
+        bail!("Streaming load does not support the ledger generator");
+    }
+    require_persistent_storage("load", &args.storage, config)?;
+
+    let storage = initialize_storage(&args.storage, config).await?;
+    let count = args.generator.count(config);
+    let chunks = DefaultTransferGenerator::new(args.generator.config(config)?)?
+        .stream(Some(count), args.chunk_size)?;
+
+    eprintln!("Потоковая загрузка трансферов...");
+    let summary = storage
+        .save_transfers_stream(chunks)
+        .await
+        .context("Failed to save transfers")?;
+    print_summary("Сохранено трансферов", &summary);
+    Ok(())
+}
+
+pub async fn analyze(args: &AnalyzeArgs, config: &ConfigFile) -> Result<()> {
+    require_persistent_storage("analyze", &args.storage, config)?;
+    let settings = args.storage.settings(&config.storage);
+    let storage = initialize_storage(&args.storage, config).await?;
+
+    let backend: Box<dyn StatsBackend> = match args.stats_backend {
+        StatsBackendKind::Process => Box::new(InProcessStatsBackend::new(storage.clone())),
+        StatsBackendKind::Clickhouse => Box::new(ClickHouseStatsBackend::new(
+            clickhouse_client(&settings),
+            settings.mode,
+        )),
+    };
+
+    eprintln!("Расчет метрик...");
+    let pipeline = args.pipeline.config(config.pipeline.as_ref())?;
+    backend.compute_stats(&pipeline).await?;
+
+    let saved_stats = storage.get_stats().await.context("Failed to load stats")?;
+    eprintln!("✓ Адресов в статистике: {}", saved_stats.len());
+    Ok(())
+}
+
+pub async fn stats(args: &StatsArgs, config: &ConfigFile) -> Result<()> {
+    if let Some(StatsCommand::Export(export)) = &args.action {
+        return export_stats(export, config).await;
+    }
+
+    require_persistent_storage("stats", &args.storage, config)?;
+    let storage = initialize_storage(&args.storage, config).await?;
+    let stats = storage.get_stats().await.context("Failed to load stats")?;
+
+    let limit = args.limit.unwrap_or(stats.len());
+    print_stats(&stats[..limit.min(stats.len())]);
+    Ok(())
+}
+
+async fn export_stats(args: &ExportArgs, config: &ConfigFile) -> Result<()> {
+    require_persistent_storage("stats export", &args.storage, config)?;
+    let storage = initialize_storage(&args.storage, config).await?;
+    let stats = storage.get_stats().await.context("Failed to load stats")?;
+
+    let options = args.options();
+    match &args.output {
+        Some(path) => write_stats_file(path, &stats, args.format(), &options)?,
+        None => export_stats_to(BufWriter::new(io::stdout()), &stats, args.format(), &options)?,
+    }
+
+    eprintln!("Выгружено адресов: {}", options.limit.unwrap_or(stats.len()).min(stats.len()));
+    Ok(())
+}
+
+pub async fn run(args: &RunArgs, config: &ConfigFile) -> Result<()> {
+    print_header();
+
+    let transfers = generate_test_data(&args.generator, config)?;
+    let storage = initialize_storage(&args.storage, config).await?;
+
+    let pipeline = args.pipeline.config(config.pipeline.as_ref())?;
+    let mode = args.storage.settings(&config.storage).mode;
+    let saved_stats = run_analysis(storage, &transfers, &pipeline, mode).await?;
+
+    if let Some(path) = &args.export {
+        let format = ExportFormat::from_path(path).unwrap_or(ExportFormat::Csv);
+        write_stats_file(path, &saved_stats, format, &ExportOptions::default())?;
+    }
+    Ok(())
+}
+
+pub async fn migrate(args: &MigrateArgs, config: &ConfigFile) -> Result<()> {
+    let settings = args.storage.settings(&config.storage);
+    if settings.backend == StorageBackend::Memory {
+        bail!("Migrations apply only to the ClickHouse backend");
+    }
+
+    let mut migrator = Migrator::new(clickhouse_client(&settings));
+    if settings.live_stats {
+        migrator = migrator.with_live_stats();
+    }
+    let applied = migrator
+        .applied()
+        .await
+        .with_context(|| format!("Failed to read schema version from {}", settings.url))?;
+
+    if args.status {
+        for migration in MIGRATIONS {
+            let mark = if applied.contains(&migration.version) { "✓" } else { " " };
+            let live = if migration.live_stats { " (--live-stats)" } else { "" };
+            println!("{} {:>4} {}{}", mark, migration.version, migration.name, live);
+        }
+        return Ok(());
+    }
+
+    let current = applied.last().copied().unwrap_or_default();
+    let target = args.to.unwrap_or_else(|| migrations::latest_required(settings.live_stats));
+    if target >= current {
+        for version in migrator.up(Some(target)).await.context("Failed to apply migrations")? {
+            eprintln!("✓ Применена миграция {}", version);
+        }
+    } else {
+        for version in migrator.down(target).await.context("Failed to revert migrations")? {
+            eprintln!("✓ Откачена миграция {}", version);
+        }
+    }
+
+    eprintln!("Версия схемы: {}", migrator.current_version().await?);
+    Ok(())
+}
+
+fn print_header() {
+    eprintln!("=== Сервис анализа трансферов токенов ===\n");
+}
+
+fn generate_test_data(args: &GeneratorArgs, config: &ConfigFile) -> Result<Vec<Transfer>> {
+    eprintln!("Генерируем тестовые данные...");
+
+    let generator: Box<dyn TransferGenerator> = match args.ledger_config(config)? {
+        Some(ledger) => Box::new(LedgerTransferGenerator::new(args.config(config)?, ledger)?),
+        None => Box::new(DefaultTransferGenerator::new(args.config(config)?)?),
+    };
+    let transfers = generator.generate(args.count(config))?;
+    eprintln!("Сгенерировано {} трансферов\n", transfers.len());
+
+    Ok(transfers)
+}
+
+/// Commands other than `run` hand data to each other through the storage, so
+/// the memory backend would lose it when the process exits.
+fn require_persistent_storage(
+    command: &str,
+    args: &StorageArgs,
+    config: &ConfigFile,
+) -> Result<()> {
+    if args.settings(&config.storage).backend == StorageBackend::Memory {
+        bail!(
+            "{} needs the ClickHouse storage backend; the memory backend keeps data only \
+             within one process, use run instead",
+            command
+        );
+    }
+    Ok(())
+}
+
+async fn initialize_storage(args: &StorageArgs, config: &ConfigFile) -> Result<Arc<dyn Storage>> {
+    let settings = args.settings(&config.storage);
+
+    match settings.backend {
+        StorageBackend::Memory => Ok(Arc::new(InMemoryStorage::with_mode(settings.mode))),
+        StorageBackend::Clickhouse => {
+            eprintln!("Подключение к ClickHouse...");
+
+            let client = clickhouse_client(&settings);
+            let insert = ChunkedInsert::new(settings.insert)
+                .context("Invalid insert settings")?
+                .on_progress(print_progress);
+
+            let mut storage = ClickHouseStorage::with_options(client, insert, settings.mode)
+                .await
+                .with_context(|| format!("Failed to connect to {}", settings.url))?;
+            if settings.live_stats {
+                storage = storage
+                    .with_live_stats()
+                    .await
+                    .context("Failed to enable live stats")?;
+            }
+            eprintln!("✓ Подключение к ClickHouse успешно!\n");
+            Ok(Arc::new(storage))
+        }
+    }
+}
+
+fn clickhouse_client(settings: &StorageSettings) -> Client {
+    let mut client = Client::default().with_url(&settings.url);
+    if let Some(user) = &settings.user {
+        client = client.with_user(user);
+    }
+    if let Some(password) = &settings.password {
+        client = client.with_password(password);
+    }
+    if let Some(database) = &settings.database {
+        client = client.with_database(database);
+    }
+    client
+}
+
+async fn run_analysis(
+    storage: Arc<dyn Storage>,
+    transfers: &[Transfer],
+    config: &PipelineConfig,
+    mode: SaveMode,
+) -> Result<Vec<UserStats>> {
+    save_transfers(&storage, transfers).await?;
+    match mode {
+        SaveMode::Replace => calculate_and_save_statistics(&storage, transfers, config).await?,
+        // Appended transfers join those of earlier runs, which the stats cover too.
+        SaveMode::Append => {
+            let stored = storage.get_transfers().await.context("Failed to load transfers")?;
+            calculate_and_save_statistics(&storage, &stored, config).await?
+        }
+    };
+    let saved_stats = storage.get_stats().await.context("Failed to load stats")?;
+    eprintln!("Анализ завершен успешно! Адресов в статистике: {}", saved_stats.len());
+    Ok(saved_stats)
+}
+
+async fn save_transfers(storage: &Arc<dyn Storage>, transfers: &[Transfer]) -> Result<()> {
+    eprintln!("Сохранение трансферов...");
+    let summary = storage
+        .save_transfers(transfers)
+        .await
+        .context("Failed to save transfers")?;
+    print_summary("Трансферы сохранены", &summary);
+    Ok(())
+}
+
+async fn calculate_and_save_statistics(
+    storage: &Arc<dyn Storage>,
+    transfers: &[Transfer],
+    config: &PipelineConfig,
+) -> Result<Vec<UserStats>> {
+    eprintln!("Расчет метрик...");
+    let stats = calculate_user_stats_with(transfers, config)?;
+    eprintln!("✓ Рассчитано метрик для {} адресов", stats.len());
+
+    eprintln!("Сохранение статистики...");
+    let summary = storage.save_stats(&stats).await.context("Failed to save stats")?;
+    print_summary("Статистика сохранена", &summary);
+    eprintln!();
+
+    Ok(stats)
+}
+
+fn print_progress(progress: &InsertProgress) {
+    eprint!(
+        "  {}: пакет {} — {} строк, всего {}",
+        progress.table, progress.chunk, progress.rows, progress.written
+    );
+    if progress.retries > 0 {
+        eprint!(" (повторов: {})", progress.retries);
+    }
+    eprintln!();
+}
+
+fn print_summary(message: &str, summary: &InsertSummary) {
+    eprintln!(
+        "✓ {}: {} строк, пакетов {}, повторов {}",
+        message, summary.rows, summary.chunks, summary.retries
+    );
+}
+
+fn write_transfers<W: Write>(mut writer: W, transfers: &[Transfer]) -> Result<()> {
+    for transfer in transfers {
+        serde_json::to_writer(&mut writer, transfer).context("Failed to serialize transfer")?;
+        writeln!(writer).context("Failed to write transfer")?;
+    }
+    writer.flush().context("Failed to flush output")
+}
+
+fn write_stats_file(
+    path: &Path,
+    stats: &[UserStats],
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    export_stats_to(BufWriter::new(file), stats, format, options)
+        .with_context(|| format!("Failed to export stats to {}", path.display()))
+}
+
+fn export_stats_to<W: Write + Send>(
+    writer: W,
+    stats: &[UserStats],
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<()> {
+    export::export_stats(writer, stats, format, options).context("Failed to export stats")
+}
+
+fn import_transfers<R: BufRead>(
+    reader: R,
+    args: &IngestArgs,
+    options: &ImportOptions,
+) -> Result<ImportReport> {
+    let report = match args.format() {
+        InputFormat::Jsonl => read_ndjson(reader, options),
+        InputFormat::Csv => read_csv(reader, options),
+        InputFormat::Erc20Logs => return decode_erc20_logs(reader, &args.erc20, options.max_errors),
+    };
+    report.context("Failed to import transfers")
+}
+
+fn decode_erc20_logs<R: BufRead>(
+    mut reader: R,
+    args: &Erc20Args,
+    max_errors: Option<usize>,
+) -> Result<ImportReport> {
+    let Some(usd_price) = args.usd_price else {
+        bail!("--usd-price is required for erc20-logs input");
+    };
+
+    let mut json = String::new();
+    reader.read_to_string(&mut json).context("Failed to read logs")?;
+    let logs = erc20::parse_logs(&json).context("Invalid eth_getLogs response")?;
+
+    let timestamps = match &args.blocks {
+        Some(path) => {
+            let blocks = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            erc20::parse_block_timestamps(&blocks)
+                .with_context(|| format!("Invalid blocks file {}", path.display()))?
+        }
+        None => HashMap::new(),
+    };
+
+    let config = Erc20Config {
+        decimals: args.decimals,
+        token: args.token,
+        max_errors,
+    };
+    erc20::decode_logs(&logs, &config, &timestamps, &FixedPrice(usd_price))
+        .context("Failed to decode logs")
+}
+
+fn print_stats(stats: &[UserStats]) {
+    let mut windows: Vec<&String> = stats
+        .first()
+        .map(|s| s.max_balance_windows.keys().collect())
+        .unwrap_or_default();
+    windows.sort_by_key(|w| BalanceWindow::label_order(w));
+
+    print!(
+        "{:<42} {:>14} {:>10} {:>10} {:>14}",
+        "address", "total_volume", "avg_buy", "avg_sell", "max_balance"
+    );
+    for window in &windows {
+        print!(" {:>14}", format!("max_{}", window));
+    }
+    println!();
+
+    for s in stats {
+        print!(
+            "{:<42} {:>14.4} {:>10.4} {:>10.4} {:>14.4}",
+            s.address,
+            s.total_volume.to_f64(),
+            s.avg_buy_price,
+            s.avg_sell_price,
+            s.max_balance.to_f64(),
+        );
+        for window in &windows {
+            let value = s.max_balance_windows.get(*window).copied().unwrap_or_default();
+            let value = value.to_f64();
+            print!(" {:>14.4}", value);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use mycrate::generator::config::TransferGenConfig;
+use mycrate::generator::ledger::LedgerConfig;
+use mycrate::pipeline::PipelineConfig;
+use mycrate::storage::{InsertConfig, SaveMode};
+
+use crate::cli::args::StorageBackend;
+
+/// Scenario preset passed with `--config`.
+///
+/// Every value is optional; env vars and CLI flags take precedence over the
+/// file, which takes precedence over built-in defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Number of transfers to generate.
+    pub count: Option<usize>,
+    pub generator: TransferGenConfig,
+    /// Generate in ledger mode with these settings.
+    pub ledger: Option<LedgerConfig>,
+    pub pipeline: Option<PipelineConfig>,
+    pub storage: StorageFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageFile {
+    pub backend: Option<StorageBackend>,
+    pub url: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+    pub save_mode: Option<SaveMode>,
+    /// Chunking and retry of ClickHouse inserts.
+    pub insert: InsertConfig,
+    /// Provision and read the ClickHouse live aggregates.
+    pub live_stats: bool,
+}
+
+impl ConfigFile {
+    /// Reads a TOML (`.toml`) or YAML (`.yaml`, `.yml`) file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+
+        let config = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&text).map_err(anyhow::Error::from),
+            Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(anyhow::Error::from),
+            _ => bail!(
+                "Unsupported config format {}, expected .toml, .yaml or .yml",
+                path.display()
+            ),
+        };
+        config.with_context(|| format!("Invalid config {}", path.display()))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod args;
+pub mod config;
+pub mod commands;
+
+pub use args::{Cli, Command};
@@ -0,0 +1,32 @@
+use std::io::Write;
+
+use ::csv::Writer;
+
+use crate::export::errors::ExportError;
+use crate::export::options::ExportOptions;
+use crate::export::table::{cell, Cell, Table};
+use crate::model::UserStats;
+
+/// Writes a header row and one row per address; missing windows are empty.
+/// Amounts are exact decimals.
+pub fn write_csv<W: Write>(
+    writer: W,
+    stats: &[UserStats],
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let table = Table::new(stats, options);
+    let mut writer = Writer::from_writer(writer);
+
+    writer.write_record(table.columns.iter().map(ToString::to_string))?;
+    for row in &table.rows {
+        writer.write_record(table.columns.iter().map(|column| match cell(row, column) {
+            Cell::Address(address) => address.to_string(),
+            Cell::Amount(amount) => amount.to_string(),
+            Cell::Number(value) => value.to_string(),
+            Cell::Missing => String::new(),
+        }))?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use std::io::Write;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::export::errors::ExportError;
+use crate::export::options::{ExportOptions, StatsColumn};
+use crate::export::table::{cell, Cell, Table};
+use crate::model::UserStats;
+
+/// An object with the selected columns as keys, in column order. Amounts are
+/// exact decimal strings, since JSON numbers are read as `f64`.
+struct Row<'a> {
+    stats: &'a UserStats,
+    columns: &'a [StatsColumn],
+}
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for column in self.columns {
+            let key = column.to_string();
+            match cell(self.stats, column) {
+                Cell::Address(address) => map.serialize_entry(&key, &address)?,
+                Cell::Amount(amount) => map.serialize_entry(&key, &amount)?,
+                Cell::Number(value) => map.serialize_entry(&key, &value)?,
+                Cell::Missing => map.serialize_entry(&key, &())?,
+            }
+        }
+        map.end()
+    }
+}
+
+fn rows<'a>(table: &'a Table<'a>) -> impl Iterator<Item = Row<'a>> {
+    table.rows.iter().map(|stats| Row {
+        stats,
+        columns: &table.columns,
+    })
+}
+
+/// Writes a pretty-printed array of objects.
+pub fn write_json<W: Write>(
+    mut writer: W,
+    stats: &[UserStats],
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let table = Table::new(stats, options);
+    let rows: Vec<Row<'_>> = rows(&table).collect();
+
+    serde_json::to_writer_pretty(&mut writer, &rows)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes one compact object per line.
+pub fn write_ndjson<W: Write>(
+    mut writer: W,
+    stats: &[UserStats],
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let table = Table::new(stats, options);
+
+    for row in rows(&table) {
+        serde_json::to_writer(&mut writer, &row)?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
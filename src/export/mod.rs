@@ -0,0 +1,40 @@
+//! Writers that save [`UserStats`](crate::model::UserStats) to files.
+//!
+//! CSV, JSON (a pretty-printed array), NDJSON and, with the `parquet` cargo
+//! feature, Parquet are supported. Every format shares [`ExportOptions`] for
+//! column selection, sorting and row limits; by default rows follow
+//! [`GetStatsQuery`](crate::storage::GetStatsQuery) ordering, `total_volume`
+//! descending.
+
+pub mod csv;
+pub mod errors;
+pub mod json;
+pub mod options;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+mod table;
+
+pub use errors::ExportError;
+pub use options::{ExportFormat, ExportOptions, SortOrder, StatsColumn};
+
+use std::io::Write;
+
+use crate::model::UserStats;
+
+/// Writes `stats` to `writer` in `format`.
+pub fn export_stats<W: Write + Send>(
+    writer: W,
+    stats: &[UserStats],
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    match format {
+        ExportFormat::Csv => csv::write_csv(writer, stats, options),
+        ExportFormat::Json => json::write_json(writer, stats, options),
+        ExportFormat::Ndjson => json::write_ndjson(writer, stats, options),
+        #[cfg(feature = "parquet")]
+        ExportFormat::Parquet => parquet::write_parquet(writer, stats, options),
+        #[cfg(not(feature = "parquet"))]
+        ExportFormat::Parquet => Err(ExportError::FeatureDisabled("parquet")),
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::bail;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// A pretty-printed JSON array.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Needs the `parquet` cargo feature.
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Format matching the file extension, if it is a known one.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "csv" => Self::Csv,
+            "json" => Self::Json,
+            "ndjson" | "jsonl" => Self::Ndjson,
+            "parquet" => Self::Parquet,
+            _ => bail!("Unknown export format '{}', expected csv, json, ndjson or parquet", s),
+        })
+    }
+}
+
+/// A [`UserStats`](crate::model::UserStats) field as an output column.
+///
+/// Windowed maxima are named `max_balance_<window>`, e.g. `max_balance_24h`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsColumn {
+    Address,
+    TotalVolume,
+    AvgBuyPrice,
+    AvgSellPrice,
+    MaxBalance,
+    MaxBalanceWindow(String),
+}
+
+impl StatsColumn {
+    pub const FIXED: [StatsColumn; 5] = [
+        Self::Address,
+        Self::TotalVolume,
+        Self::AvgBuyPrice,
+        Self::AvgSellPrice,
+        Self::MaxBalance,
+    ];
+}
+
+impl FromStr for StatsColumn {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "address" => Self::Address,
+            "total_volume" => Self::TotalVolume,
+            "avg_buy_price" => Self::AvgBuyPrice,
+            "avg_sell_price" => Self::AvgSellPrice,
+            "max_balance" => Self::MaxBalance,
+            other => match other.strip_prefix("max_balance_") {
+                Some(window) if !window.is_empty() => Self::MaxBalanceWindow(window.to_string()),
+                _ => bail!("Unknown stats column '{}'", other),
+            },
+        })
+    }
+}
+
+impl fmt::Display for StatsColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address => f.write_str("address"),
+            Self::TotalVolume => f.write_str("total_volume"),
+            Self::AvgBuyPrice => f.write_str("avg_buy_price"),
+            Self::AvgSellPrice => f.write_str("avg_sell_price"),
+            Self::MaxBalance => f.write_str("max_balance"),
+            Self::MaxBalanceWindow(window) => write!(f, "max_balance_{}", window),
+        }
+    }
+}
+
+/// Row order; ties are broken by address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOrder {
+    pub column: StatsColumn,
+    pub descending: bool,
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        Self {
+            column: StatsColumn::TotalVolume,
+            descending: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Columns to write in order; empty writes every fixed column followed by
+    /// each window found in the stats.
+    pub columns: Vec<StatsColumn>,
+    pub sort: SortOrder,
+    /// Write only the first rows after sorting.
+    pub limit: Option<usize>,
+}
@@ -0,0 +1,75 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use ::parquet::arrow::ArrowWriter;
+use arrow_array::{ArrayRef, Decimal128Array, Float64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+
+use crate::export::errors::ExportError;
+use crate::export::options::{ExportOptions, StatsColumn};
+use crate::export::table::{cell, Cell, Table};
+use crate::model::{Amount, UserStats};
+
+/// `Decimal128(18)` as stored in ClickHouse.
+const AMOUNT_PRECISION: u8 = 38;
+const AMOUNT_SCALE: i8 = Amount::DECIMALS as i8;
+
+/// Writes a single row group with `address` as UTF-8, amounts as nullable
+/// `DECIMAL(38, 18)` and prices as nullable `DOUBLE`.
+pub fn write_parquet<W: Write + Send>(
+    writer: W,
+    stats: &[UserStats],
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let table = Table::new(stats, options);
+
+    let fields: Vec<Field> = table
+        .columns
+        .iter()
+        .map(|column| match column {
+            StatsColumn::Address => Field::new(column.to_string(), DataType::Utf8, false),
+            StatsColumn::AvgBuyPrice | StatsColumn::AvgSellPrice => {
+                Field::new(column.to_string(), DataType::Float64, true)
+            }
+            _ => Field::new(
+                column.to_string(),
+                DataType::Decimal128(AMOUNT_PRECISION, AMOUNT_SCALE),
+                true,
+            ),
+        })
+        .collect();
+    let schema = Arc::new(Schema::new(fields));
+
+    let arrays: Vec<ArrayRef> = table
+        .columns
+        .iter()
+        .map(|column| -> Result<ArrayRef, ExportError> {
+            Ok(match column {
+                StatsColumn::Address => Arc::new(StringArray::from_iter_values(
+                    table.rows.iter().map(|s| s.address.to_string()),
+                )),
+                StatsColumn::AvgBuyPrice | StatsColumn::AvgSellPrice => {
+                    Arc::new(Float64Array::from_iter(table.rows.iter().map(|s| {
+                        match cell(s, column) {
+                            Cell::Number(value) => Some(value),
+                            _ => None,
+                        }
+                    })))
+                }
+                _ => Arc::new(
+                    Decimal128Array::from_iter(table.rows.iter().map(|s| match cell(s, column) {
+                        Cell::Amount(amount) => Some(amount.raw()),
+                        _ => None,
+                    }))
+                    .with_precision_and_scale(AMOUNT_PRECISION, AMOUNT_SCALE)?,
+                ),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+
+    let mut writer = ArrowWriter::try_new(writer, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
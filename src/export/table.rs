@@ -0,0 +1,83 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use crate::export::options::{ExportOptions, StatsColumn};
+use crate::model::{Address, Amount, UserStats};
+use crate::pipeline::BalanceWindow;
+
+/// A single output value; windows absent from a row are `Missing`.
+pub(crate) enum Cell {
+    Address(Address),
+    /// Token amounts, written exactly.
+    Amount(Amount),
+    /// Prices, which are `f64` already.
+    Number(f64),
+    Missing,
+}
+
+/// Stats rows in output order together with the columns to write.
+pub(crate) struct Table<'a> {
+    pub columns: Vec<StatsColumn>,
+    pub rows: Vec<&'a UserStats>,
+}
+
+impl<'a> Table<'a> {
+    pub fn new(stats: &'a [UserStats], options: &ExportOptions) -> Self {
+        let columns = if options.columns.is_empty() {
+            let windows: BTreeSet<&String> =
+                stats.iter().flat_map(|s| s.max_balance_windows.keys()).collect();
+            let mut windows: Vec<&String> = windows.into_iter().collect();
+            windows.sort_by_key(|w| BalanceWindow::label_order(w));
+            StatsColumn::FIXED
+                .into_iter()
+                .chain(windows.into_iter().map(|w| StatsColumn::MaxBalanceWindow(w.clone())))
+                .collect()
+        } else {
+            options.columns.clone()
+        };
+
+        let sort = &options.sort;
+        let mut rows: Vec<&UserStats> = stats.iter().collect();
+        rows.sort_by(|a, b| {
+            let ordering = compare(&cell(a, &sort.column), &cell(b, &sort.column));
+            let ordering = if sort.descending { ordering.reverse() } else { ordering };
+            ordering.then_with(|| a.address.cmp(&b.address))
+        });
+        rows.truncate(options.limit.unwrap_or(rows.len()));
+
+        Self { columns, rows }
+    }
+}
+
+pub(crate) fn cell(stats: &UserStats, column: &StatsColumn) -> Cell {
+    match column {
+        StatsColumn::Address => Cell::Address(stats.address),
+        StatsColumn::TotalVolume => Cell::Amount(stats.total_volume),
+        StatsColumn::AvgBuyPrice => Cell::Number(stats.avg_buy_price),
+        StatsColumn::AvgSellPrice => Cell::Number(stats.avg_sell_price),
+        StatsColumn::MaxBalance => Cell::Amount(stats.max_balance),
+        StatsColumn::MaxBalanceWindow(window) => stats
+            .max_balance_windows
+            .get(window)
+            .map_or(Cell::Missing, |&v| Cell::Amount(v)),
+    }
+}
+
+/// Missing values sort before everything else.
+fn compare(a: &Cell, b: &Cell) -> Ordering {
+    match (a, b) {
+        (Cell::Address(a), Cell::Address(b)) => a.cmp(b),
+        (Cell::Amount(a), Cell::Amount(b)) => a.cmp(b),
+        (Cell::Number(a), Cell::Number(b)) => a.total_cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn rank(cell: &Cell) -> u8 {
+    match cell {
+        Cell::Missing => 0,
+        Cell::Address(_) => 1,
+        Cell::Amount(_) => 2,
+        Cell::Number(_) => 3,
+    }
+}
@@ -1,10 +1,46 @@
-#[derive(Debug, Clone)]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+
+use crate::generator::errors::{check, check_range, ConfigError};
+use crate::generator::pool::AddressPoolConfig;
+use crate::generator::price::{PricePath, PriceProcessConfig};
+use crate::generator::timing::{TimePatternConfig, Timeline};
+use crate::model::Amount;
+
+/// Largest `max_amount`, keeping amounts well inside `Decimal128(18)`.
+const MAX_AMOUNT: f64 = 1e20;
+
+/// Generator settings; deserialisable with every field optional.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct TransferGenConfig {
     pub min_amount: f64,
     pub max_amount: f64,
     pub min_price: f64,
     pub max_price: f64,
     pub max_age_secs: u64,
+    /// Seed for the random generator; identical configs with a seed produce
+    /// identical transfers.
+    pub seed: Option<u64>,
+    /// Unix timestamp used instead of the current time.
+    pub now: Option<u64>,
+    /// Draw addresses from a bounded pool instead of a fresh random address
+    /// per transfer side.
+    pub pool: Option<AddressPoolConfig>,
+    /// Price transfers along a simulated series instead of drawing
+    /// `min_price..max_price` independently.
+    pub price_process: Option<PriceProcessConfig>,
+    /// Start of the timestamp range, `end - max_age_secs` when unset.
+    pub start_ts: Option<u64>,
+    /// End of the timestamp range, `now` when unset.
+    pub end_ts: Option<u64>,
+    pub time_pattern: TimePatternConfig,
+    /// Emit transfers ordered by `ts`.
+    pub sorted: bool,
 }
 
 impl Default for TransferGenConfig {
@@ -15,6 +51,102 @@ impl Default for TransferGenConfig {
             min_price: 0.1,
             max_price: 2.0,
             max_age_secs: 86_400 * 30, // 30 дней
+            seed: None,
+            now: None,
+            pool: None,
+            price_process: None,
+            start_ts: None,
+            end_ts: None,
+            time_pattern: TimePatternConfig::default(),
+            sorted: false,
+        }
+    }
+}
+
+impl TransferGenConfig {
+    /// Checks every field, including the nested pool, price process and time
+    /// pattern, so that generation cannot panic or underflow.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_range("amount", self.min_amount, self.max_amount, "a non-negative lower bound", |v| {
+            v >= 0.0
+        })?;
+        check("amount", self.max_amount, "at most 1e20", |v| v <= MAX_AMOUNT)?;
+        check_range("price", self.min_price, self.max_price, "a positive lower bound", |v| v > 0.0)?;
+
+        let end = match self.end_ts {
+            Some(end) => end,
+            None => self.current_time()?,
+        };
+        match self.start_ts {
+            Some(start) if start > end => return Err(ConfigError::TimeRange { start, end }),
+            Some(_) => {}
+            None if self.max_age_secs > end => {
+                return Err(ConfigError::MaxAgeBeforeEpoch {
+                    max_age_secs: self.max_age_secs,
+                    end,
+                })
+            }
+            None => {}
+        }
+
+        if let Some(pool) = &self.pool {
+            pool.validate()?;
+        }
+        if let Some(price_process) = &self.price_process {
+            price_process.validate()?;
+        }
+        self.time_pattern.validate()
+    }
+
+    /// Samples an amount in `min_amount..max`.
+    pub(crate) fn sample_amount<R: Rng + ?Sized>(&self, max: f64, rng: &mut R) -> Amount {
+        let amount = if max > self.min_amount {
+            rng.random_range(self.min_amount..max)
+        } else {
+            self.min_amount
+        };
+        Amount::from_f64(amount).expect("validate() bounds amounts to Decimal128(18)")
+    }
+
+    pub(crate) fn rng(&self) -> ChaCha8Rng {
+        match self.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    /// Timestamp range `(start, end]` transfers are generated in.
+    pub(crate) fn time_range(&self) -> Result<(u64, u64)> {
+        let end = match self.end_ts {
+            Some(end) => end,
+            None => self.now()?,
+        };
+        let start = self.start_ts.unwrap_or(end.saturating_sub(self.max_age_secs));
+        Ok((start, end))
+    }
+
+    pub(crate) fn timeline<R: Rng + ?Sized>(&self, start: u64, end: u64, rng: &mut R) -> Result<Timeline> {
+        Timeline::new(&self.time_pattern, start, end, rng).context("Failed to build timeline")
+    }
+
+    /// Price path starting at the beginning of the time range.
+    pub(crate) fn price_path(&self, start: u64) -> Result<Option<PricePath>> {
+        self.price_process
+            .clone()
+            .map(|config| PricePath::new(config, start))
+            .transpose()
+            .context("Failed to build price path")
+    }
+
+    pub(crate) fn now(&self) -> Result<u64> {
+        self.current_time()
+            .context("Failed to get duration since UNIX_EPOCH")
+    }
+
+    fn current_time(&self) -> Result<u64, ConfigError> {
+        match self.now {
+            Some(now) => Ok(now),
+            None => Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()),
         }
     }
 }
@@ -0,0 +1,73 @@
+use std::time::SystemTimeError;
+
+use thiserror::Error;
+
+/// Reasons a generator configuration is rejected by `validate()`.
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Invalid {field}: expected {expected}, got {value}")]
+    InvalidValue {
+        field: &'static str,
+        expected: &'static str,
+        value: f64,
+    },
+    #[error("Empty {field} range: min {min} must be below max {max}")]
+    EmptyRange {
+        field: &'static str,
+        min: f64,
+        max: f64,
+    },
+    #[error("Time range start {start} is after end {end}")]
+    TimeRange { start: u64, end: u64 },
+    #[error("max_age_secs {max_age_secs} reaches before the Unix epoch from {end}")]
+    MaxAgeBeforeEpoch { max_age_secs: u64, end: u64 },
+    #[error("Address pool needs at least 2 regular addresses, got {size}")]
+    PoolTooSmall { size: usize },
+    #[error("Ledger generator needs at least 3 regular addresses, got {size}")]
+    LedgerPoolTooSmall { size: usize },
+    #[error("Exchange and whale shares leave no room for regular addresses: {exchange_share} + {whale_share} >= 1")]
+    PoolShares { exchange_share: f64, whale_share: f64 },
+    #[error("Seasonality peak hour must be below 24, got {peak_hour}")]
+    PeakHour { peak_hour: u32 },
+    #[error("System clock error: {0}")]
+    Clock(#[from] SystemTimeError),
+}
+
+/// Checks that `value` is finite and inside the range described by `expected`.
+pub(crate) fn check(
+    field: &'static str,
+    value: f64,
+    expected: &'static str,
+    valid: impl FnOnce(f64) -> bool,
+) -> Result<(), ConfigError> {
+    if value.is_finite() && valid(value) {
+        Ok(())
+    } else {
+        Err(ConfigError::InvalidValue {
+            field,
+            expected,
+            value,
+        })
+    }
+}
+
+pub(crate) fn check_probability(field: &'static str, value: f64) -> Result<(), ConfigError> {
+    check(field, value, "a probability in [0, 1]", |v| (0.0..=1.0).contains(&v))
+}
+
+/// Checks a half-open `min..max` range whose lower bound satisfies `valid_min`,
+/// described by `expected`.
+pub(crate) fn check_range(
+    field: &'static str,
+    min: f64,
+    max: f64,
+    expected: &'static str,
+    valid_min: impl FnOnce(f64) -> bool,
+) -> Result<(), ConfigError> {
+    check(field, min, expected, valid_min)?;
+    check(field, max, "a finite upper bound", |_| true)?;
+    if min >= max {
+        return Err(ConfigError::EmptyRange { field, min, max });
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::generator::config::TransferGenConfig;
+use crate::generator::errors::{check_probability, ConfigError};
+use crate::generator::pool::{AddressPool, AddressPoolConfig, MINT_ADDRESS};
+use crate::generator::price::price_transfers;
+use crate::generator::transfer::TransferGenerator;
+use crate::model::{Address, Amount, Transfer};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LedgerConfig {
+    /// Sender of every mint transfer; the only address allowed to go negative.
+    pub genesis_address: Address,
+    /// Probability that a transfer mints new supply while holders exist.
+    pub mint_share: f64,
+}
+
+impl LedgerConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_probability("mint_share", self.mint_share)
+    }
+}
+
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        Self {
+            genesis_address: MINT_ADDRESS,
+            mint_share: 0.05,
+        }
+    }
+}
+
+/// Balances of addresses that can afford at least `min_amount`.
+struct Ledger {
+    min_amount: Amount,
+    balances: HashMap<Address, Amount>,
+    holders: Vec<Address>,
+    holder_index: HashMap<Address, usize>,
+}
+
+impl Ledger {
+    fn new(min_amount: Amount) -> Self {
+        Self {
+            min_amount,
+            balances: HashMap::new(),
+            holders: Vec::new(),
+            holder_index: HashMap::new(),
+        }
+    }
+
+    fn balance(&self, address: Address) -> Amount {
+        self.balances.get(&address).copied().unwrap_or_default()
+    }
+
+    fn random_holder<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Address> {
+        if self.holders.is_empty() {
+            return None;
+        }
+        Some(self.holders[rng.random_range(0..self.holders.len())])
+    }
+
+    /// Saturates: only the genesis balance, which goes negative with every
+    /// mint, can reach the limits.
+    fn apply(&mut self, address: Address, delta: Amount) {
+        let balance = self.balances.entry(address).or_default();
+        *balance = balance.saturating_add(delta);
+        let can_send = *balance >= self.min_amount;
+
+        match (can_send, self.holder_index.get(&address).copied()) {
+            (true, None) => {
+                self.holder_index.insert(address, self.holders.len());
+                self.holders.push(address);
+            }
+            (false, Some(index)) => {
+                self.holders.swap_remove(index);
+                self.holder_index.remove(&address);
+                if let Some(moved) = self.holders.get(index) {
+                    self.holder_index.insert(*moved, index);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Generator whose senders never spend more than they received.
+///
+/// Supply enters only through mint transfers from
+/// [`LedgerConfig::genesis_address`]. Transfers are emitted in timestamp
+/// order so replaying them reproduces the ledger. Addresses come from
+/// [`TransferGenConfig::pool`], or a default pool when unset.
+pub struct LedgerTransferGenerator {
+    config: TransferGenConfig,
+    ledger: LedgerConfig,
+}
+
+impl LedgerTransferGenerator {
+    pub fn new(config: TransferGenConfig, ledger: LedgerConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+        ledger.validate()?;
+        // A receiver differs from both the sender and the genesis address.
+        let size = config.pool.as_ref().map_or(AddressPoolConfig::default().size, |p| p.size);
+        if size < 3 {
+            return Err(ConfigError::LedgerPoolTooSmall { size });
+        }
+        Ok(Self { config, ledger })
+    }
+
+    /// The validated configuration.
+    pub fn config(&self) -> &TransferGenConfig {
+        &self.config
+    }
+
+    pub fn ledger(&self) -> &LedgerConfig {
+        &self.ledger
+    }
+}
+
+impl TransferGenerator for LedgerTransferGenerator {
+    fn generate(&self, count: usize) -> Result<Vec<Transfer>> {
+        let mut rng = self.config.rng();
+        let (start, end) = self.config.time_range()?;
+        let pool = AddressPool::new(self.config.pool.clone().unwrap_or_default(), &mut rng)
+            .context("Failed to build address pool")?;
+        let mut timeline = self.config.timeline(start, end, &mut rng)?;
+
+        let mut timestamps: Vec<u64> =
+            (0..count).map_while(|_| timeline.next_ts(&mut rng)).collect();
+        timestamps.sort_unstable();
+
+        let genesis = self.ledger.genesis_address;
+        let min_amount = Amount::from_f64(self.config.min_amount)
+            .context("min_amount is out of the Decimal128(18) range")?;
+        let mut ledger = Ledger::new(min_amount);
+
+        let mut transfers: Vec<Transfer> = timestamps
+            .into_iter()
+            .map(|ts| {
+                let mint = rng.random_bool(self.ledger.mint_share);
+                let sender = match ledger.random_holder(&mut rng) {
+                    Some(holder) if !mint => holder,
+                    _ => genesis,
+                };
+
+                let to = pool
+                    .pick_except(&mut rng, &[sender, genesis])
+                    .expect("new() keeps at least 3 regular addresses");
+
+                let amount = if sender == genesis {
+                    self.config.sample_amount(self.config.max_amount, &mut rng)
+                } else {
+                    let balance = ledger.balance(sender);
+                    let max_amount = self.config.max_amount.min(balance.to_f64());
+                    self.config.sample_amount(max_amount, &mut rng).min(balance)
+                };
+                let usd_price = rng.random_range(self.config.min_price..self.config.max_price);
+
+                ledger.apply(sender, Amount::ZERO.saturating_sub(amount));
+                ledger.apply(to, amount);
+
+                Transfer {
+                    ts,
+                    from: sender,
+                    to,
+                    amount,
+                    usd_price,
+                }
+            })
+            .collect();
+
+        if let Some(mut path) = self.config.price_path(start)? {
+            price_transfers(&mut path, &mut transfers, &mut rng);
+        }
+
+        Ok(transfers)
+    }
+}
@@ -1,5 +1,11 @@
 pub mod address;
 pub mod config;
+pub mod errors;
+pub mod ledger;
+pub mod pool;
+pub mod price;
+pub mod stream;
+pub mod timing;
 pub mod transfer;
 
 pub use transfer::generate_transfers;
@@ -0,0 +1,171 @@
+use anyhow::{Context, Result};
+use rand::Rng;
+use rand_distr::{Distribution, Zipf};
+use serde::Deserialize;
+
+use crate::generator::address::rand_address;
+use crate::generator::errors::{check, check_probability, ConfigError};
+use crate::model::Address;
+
+/// Address that mints new supply, as in ERC-20 `Transfer(0x0, to, amount)`.
+pub const MINT_ADDRESS: Address = Address::ZERO;
+
+/// Receiver draws [`AddressPool::pick_pair`] makes before falling back.
+const MAX_PICK_ATTEMPTS: usize = 16;
+
+/// Bounded population of addresses transfers are drawn from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AddressPoolConfig {
+    /// Number of regular addresses.
+    pub size: usize,
+    /// Zipf exponent of regular address popularity; higher is more skewed.
+    pub zipf_exponent: f64,
+    pub exchanges: usize,
+    /// Probability that a transfer side is an exchange.
+    pub exchange_share: f64,
+    pub whales: usize,
+    /// Probability that a transfer side is a whale.
+    pub whale_share: f64,
+    /// Probability that a transfer is minted from [`MINT_ADDRESS`].
+    pub mint_share: f64,
+}
+
+impl AddressPoolConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.size < 2 {
+            return Err(ConfigError::PoolTooSmall { size: self.size });
+        }
+        check("zipf_exponent", self.zipf_exponent, "a non-negative exponent", |v| v >= 0.0)?;
+        check_probability("exchange_share", self.exchange_share)?;
+        check_probability("whale_share", self.whale_share)?;
+        check_probability("mint_share", self.mint_share)?;
+        if self.exchange_share + self.whale_share >= 1.0 {
+            return Err(ConfigError::PoolShares {
+                exchange_share: self.exchange_share,
+                whale_share: self.whale_share,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for AddressPoolConfig {
+    fn default() -> Self {
+        Self {
+            size: 1_000,
+            zipf_exponent: 1.1,
+            exchanges: 5,
+            exchange_share: 0.2,
+            whales: 10,
+            whale_share: 0.05,
+            mint_share: 0.01,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressRole {
+    Mint,
+    Exchange,
+    Whale,
+    Regular,
+}
+
+#[derive(Debug, Clone)]
+pub struct AddressPool {
+    config: AddressPoolConfig,
+    exchanges: Vec<Address>,
+    whales: Vec<Address>,
+    regular: Vec<Address>,
+    popularity: Zipf<f64>,
+}
+
+impl AddressPool {
+    pub fn new<R: Rng + ?Sized>(config: AddressPoolConfig, rng: &mut R) -> Result<Self> {
+        config.validate()?;
+
+        let popularity = Zipf::new(config.size as f64, config.zipf_exponent)
+            .context("Invalid Zipf exponent for address pool")?;
+
+        Ok(Self {
+            exchanges: (0..config.exchanges).map(|_| rand_address(rng)).collect(),
+            whales: (0..config.whales).map(|_| rand_address(rng)).collect(),
+            regular: (0..config.size).map(|_| rand_address(rng)).collect(),
+            popularity,
+            config,
+        })
+    }
+
+    pub fn exchanges(&self) -> &[Address] {
+        &self.exchanges
+    }
+
+    pub fn whales(&self) -> &[Address] {
+        &self.whales
+    }
+
+    /// Regular addresses, most popular first.
+    pub fn regular(&self) -> &[Address] {
+        &self.regular
+    }
+
+    pub fn role(&self, address: Address) -> Option<AddressRole> {
+        if address == MINT_ADDRESS {
+            Some(AddressRole::Mint)
+        } else if self.exchanges.contains(&address) {
+            Some(AddressRole::Exchange)
+        } else if self.whales.contains(&address) {
+            Some(AddressRole::Whale)
+        } else if self.regular.contains(&address) {
+            Some(AddressRole::Regular)
+        } else {
+            None
+        }
+    }
+
+    /// Picks a transfer participant according to role shares and popularity.
+    pub fn pick<R: Rng + ?Sized>(&self, rng: &mut R) -> Address {
+        let roll: f64 = rng.random();
+
+        if roll < self.config.exchange_share && !self.exchanges.is_empty() {
+            self.exchanges[rng.random_range(0..self.exchanges.len())]
+        } else if roll < self.config.exchange_share + self.config.whale_share
+            && !self.whales.is_empty()
+        {
+            self.whales[rng.random_range(0..self.whales.len())]
+        } else {
+            let rank = self.popularity.sample(rng) as usize;
+            self.regular[rank.clamp(1, self.regular.len()) - 1]
+        }
+    }
+
+    /// Picks an address not in `excluded`. After `MAX_PICK_ATTEMPTS` excluded
+    /// draws, falls back to the most popular regular address that is not
+    /// excluded; `None` if every regular address is.
+    pub fn pick_except<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        excluded: &[Address],
+    ) -> Option<Address> {
+        (0..MAX_PICK_ATTEMPTS)
+            .map(|_| self.pick(rng))
+            .find(|address| !excluded.contains(address))
+            .or_else(|| self.regular.iter().copied().find(|a| !excluded.contains(a)))
+    }
+
+    /// Picks distinct sender and receiver addresses, the receiver through
+    /// [`pick_except`](Self::pick_except).
+    pub fn pick_pair<R: Rng + ?Sized>(&self, rng: &mut R) -> (Address, Address) {
+        let from = if rng.random_bool(self.config.mint_share) {
+            MINT_ADDRESS
+        } else {
+            self.pick(rng)
+        };
+
+        let to = self
+            .pick_except(rng, &[from])
+            .expect("validate() keeps at least 2 regular addresses");
+        (from, to)
+    }
+}
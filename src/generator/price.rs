@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use rand::Rng;
+use rand_distr::{Distribution, Normal, Poisson, StandardNormal};
+use serde::Deserialize;
+
+use crate::generator::errors::{check, ConfigError};
+use crate::model::Transfer;
+
+const SECS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// Geometric Brownian motion with optional log-normal jumps.
+///
+/// Drift, volatility and jump intensity are annualised.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PriceProcessConfig {
+    pub initial_price: f64,
+    pub drift: f64,
+    pub volatility: f64,
+    /// Expected number of jumps per year; `0` disables jumps.
+    pub jump_intensity: f64,
+    /// Mean of the log jump size.
+    pub jump_mean: f64,
+    /// Standard deviation of the log jump size.
+    pub jump_std: f64,
+    /// Relative half-spread around the mid price applied per transfer.
+    pub spread: f64,
+}
+
+impl PriceProcessConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check("initial_price", self.initial_price, "a positive price", |v| v > 0.0)?;
+        check("drift", self.drift, "a finite drift", |_| true)?;
+        check("volatility", self.volatility, "a non-negative volatility", |v| v >= 0.0)?;
+        check("jump_intensity", self.jump_intensity, "a non-negative rate", |v| v >= 0.0)?;
+        check("jump_mean", self.jump_mean, "a finite mean", |_| true)?;
+        check("jump_std", self.jump_std, "a non-negative deviation", |v| v >= 0.0)?;
+        check("spread", self.spread, "a value in [0, 1)", |v| (0.0..1.0).contains(&v))
+    }
+}
+
+impl Default for PriceProcessConfig {
+    fn default() -> Self {
+        Self {
+            initial_price: 1.0,
+            drift: 0.0,
+            volatility: 0.8,
+            jump_intensity: 0.0,
+            jump_mean: 0.0,
+            jump_std: 0.1,
+            spread: 0.002,
+        }
+    }
+}
+
+/// Mid price series sampled at non-decreasing timestamps.
+#[derive(Debug, Clone)]
+pub struct PricePath {
+    config: PriceProcessConfig,
+    jump_size: Normal<f64>,
+    last_ts: u64,
+    price: f64,
+}
+
+impl PricePath {
+    pub fn new(config: PriceProcessConfig, start_ts: u64) -> Result<Self> {
+        config.validate()?;
+
+        let jump_size = Normal::new(config.jump_mean, config.jump_std)
+            .context("Invalid jump size distribution")?;
+
+        Ok(Self {
+            price: config.initial_price,
+            config,
+            jump_size,
+            last_ts: start_ts,
+        })
+    }
+
+    /// Mid price at `ts`. Timestamps before the last sampled one reuse the
+    /// last price.
+    pub fn mid_at<R: Rng + ?Sized>(&mut self, ts: u64, rng: &mut R) -> f64 {
+        if ts <= self.last_ts {
+            return self.price;
+        }
+
+        let dt = (ts - self.last_ts) as f64 / SECS_PER_YEAR;
+        let sigma = self.config.volatility;
+        let z: f64 = StandardNormal.sample(rng);
+        let mut log_return = (self.config.drift - sigma * sigma / 2.0) * dt + sigma * dt.sqrt() * z;
+
+        let expected_jumps = self.config.jump_intensity * dt;
+        if expected_jumps > 0.0 {
+            if let Ok(poisson) = Poisson::new(expected_jumps) {
+                let jumps = poisson.sample(rng) as u64;
+                for _ in 0..jumps {
+                    log_return += self.jump_size.sample(rng);
+                }
+            }
+        }
+
+        self.price *= log_return.exp();
+        self.last_ts = ts;
+        self.price
+    }
+
+    /// Transfer price at `ts`: the mid price shifted by a random spread.
+    pub fn price_at<R: Rng + ?Sized>(&mut self, ts: u64, rng: &mut R) -> f64 {
+        let mid = self.mid_at(ts, rng);
+        if self.config.spread == 0.0 {
+            return mid;
+        }
+        mid * (1.0 + rng.random_range(-self.config.spread..self.config.spread))
+    }
+}
+
+/// Overwrites `usd_price` of every transfer with the path price at its `ts`.
+pub fn price_transfers<R: Rng + ?Sized>(path: &mut PricePath, transfers: &mut [Transfer], rng: &mut R) {
+    let mut order: Vec<usize> = (0..transfers.len()).collect();
+    order.sort_by_key(|&i| transfers[i].ts);
+
+    for i in order {
+        transfers[i].usd_price = path.price_at(transfers[i].ts, rng);
+    }
+}
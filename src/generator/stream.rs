@@ -0,0 +1,52 @@
+use std::thread;
+
+use anyhow::{bail, Context, Result};
+use futures::stream::{self, BoxStream, StreamExt};
+use tokio::sync::mpsc;
+
+use crate::generator::transfer::DefaultTransferGenerator;
+use crate::model::Transfer;
+
+/// Chunks buffered between the generator thread and the consumer.
+const CHANNEL_CHUNKS: usize = 2;
+
+impl DefaultTransferGenerator {
+    /// Generates transfers on a background thread and yields them in chunks
+    /// of `chunk_size`; `count` of `None` never ends.
+    ///
+    /// At most a couple of chunks are held at once: generation pauses until
+    /// the consumer catches up and stops when the stream is dropped.
+    pub fn stream(
+        &self,
+        count: Option<usize>,
+        chunk_size: usize,
+    ) -> Result<BoxStream<'static, Vec<Transfer>>> {
+        if chunk_size == 0 {
+            bail!("Chunk size must be positive");
+        }
+
+        let iter = self.iter()?;
+        let (tx, rx) = mpsc::channel(CHANNEL_CHUNKS);
+
+        thread::Builder::new()
+            .name("transfer-generator".to_string())
+            .spawn(move || {
+                let mut iter: Box<dyn Iterator<Item = Transfer>> = match count {
+                    Some(count) => Box::new(iter.take(count)),
+                    None => Box::new(iter),
+                };
+                loop {
+                    let chunk: Vec<Transfer> = iter.by_ref().take(chunk_size).collect();
+                    if chunk.is_empty() || tx.blocking_send(chunk).is_err() {
+                        break;
+                    }
+                }
+            })
+            .context("Failed to spawn generator thread")?;
+
+        Ok(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        })
+        .boxed())
+    }
+}
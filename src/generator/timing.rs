@@ -0,0 +1,224 @@
+use std::f64::consts::TAU;
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use rand_distr::{Distribution, Exp};
+use serde::Deserialize;
+
+use crate::generator::errors::{check, ConfigError};
+
+const DAY: u64 = 86_400;
+const WEEK: u64 = 7 * DAY;
+/// 1970-01-01 was a Thursday; shift so that day 0 of a week is Monday.
+const EPOCH_WEEKDAY_OFFSET: u64 = 3 * DAY;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Arrival {
+    /// `count` timestamps spread independently over the whole range.
+    #[default]
+    Uniform,
+    /// Arrivals at `rate` transfers per second starting at the range start;
+    /// generation stops at the range end, possibly short of `count`.
+    Poisson { rate: f64 },
+}
+
+/// Periods of elevated activity placed at random inside the range.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BurstConfig {
+    pub count: usize,
+    pub duration_secs: u64,
+    /// Activity multiplier inside a burst.
+    pub intensity: f64,
+}
+
+/// Daily and weekly activity cycles. Amplitudes are in `[0, 1)`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Seasonality {
+    pub daily_amplitude: f64,
+    /// UTC hour of peak daily activity.
+    pub peak_hour: u32,
+    /// Weekdays get `1 + a`, weekends `1 - a`.
+    pub weekly_amplitude: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimePatternConfig {
+    pub arrival: Arrival,
+    pub bursts: Option<BurstConfig>,
+    pub seasonality: Option<Seasonality>,
+}
+
+impl Default for BurstConfig {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            duration_secs: 3_600,
+            intensity: 10.0,
+        }
+    }
+}
+
+impl Default for Seasonality {
+    fn default() -> Self {
+        Self {
+            daily_amplitude: 0.0,
+            peak_hour: 15,
+            weekly_amplitude: 0.0,
+        }
+    }
+}
+
+impl TimePatternConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Arrival::Poisson { rate } = self.arrival {
+            check("poisson_rate", rate, "a positive rate", |v| v > 0.0)?;
+        }
+        if let Some(s) = &self.seasonality {
+            let amplitude = |v: f64| (0.0..1.0).contains(&v);
+            check("daily_amplitude", s.daily_amplitude, "a value in [0, 1)", amplitude)?;
+            check("weekly_amplitude", s.weekly_amplitude, "a value in [0, 1)", amplitude)?;
+            if s.peak_hour >= 24 {
+                return Err(ConfigError::PeakHour { peak_hour: s.peak_hour });
+            }
+        }
+        if let Some(b) = &self.bursts {
+            check("burst_intensity", b.intensity, "a multiplier of at least 1", |v| v >= 1.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws transfer timestamps in `(start, end]` following a [`TimePatternConfig`].
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    start: u64,
+    end: u64,
+    arrival: Arrival,
+    seasonality: Option<Seasonality>,
+    bursts: Vec<(u64, u64)>,
+    burst_intensity: f64,
+    max_intensity: f64,
+    gaps: Option<Exp<f64>>,
+    clock: f64,
+}
+
+impl Timeline {
+    pub fn new<R: Rng + ?Sized>(
+        config: &TimePatternConfig,
+        start: u64,
+        end: u64,
+        rng: &mut R,
+    ) -> Result<Self> {
+        if start > end {
+            return Err(ConfigError::TimeRange { start, end }.into());
+        }
+        config.validate()?;
+
+        let mut max_intensity = 1.0;
+
+        if let Some(s) = &config.seasonality {
+            max_intensity *= (1.0 + s.daily_amplitude) * (1.0 + s.weekly_amplitude);
+        }
+
+        let mut bursts = Vec::new();
+        let mut burst_intensity = 1.0;
+        if let Some(b) = &config.bursts {
+            let latest_start = end.saturating_sub(b.duration_secs).max(start);
+            bursts = (0..b.count)
+                .map(|_| {
+                    let from = rng.random_range(start..=latest_start);
+                    (from, from.saturating_add(b.duration_secs))
+                })
+                .collect();
+            burst_intensity = b.intensity;
+            if !bursts.is_empty() {
+                max_intensity *= b.intensity;
+            }
+        }
+
+        let gaps = match config.arrival {
+            Arrival::Uniform => None,
+            Arrival::Poisson { rate } => Some(
+                Exp::new(rate * max_intensity).context("Invalid Poisson arrival rate")?,
+            ),
+        };
+
+        Ok(Self {
+            start,
+            end,
+            arrival: config.arrival,
+            seasonality: config.seasonality.clone(),
+            bursts,
+            burst_intensity,
+            max_intensity,
+            gaps,
+            clock: start as f64,
+        })
+    }
+
+    fn is_modulated(&self) -> bool {
+        self.max_intensity > 1.0
+    }
+
+    /// Relative activity at `ts`, between 0 and `max_intensity`.
+    fn intensity(&self, ts: u64) -> f64 {
+        let mut intensity = 1.0;
+
+        if let Some(s) = &self.seasonality {
+            let since_peak = (ts % DAY) as f64 - f64::from(s.peak_hour) * 3_600.0;
+            intensity *= 1.0 + s.daily_amplitude * (TAU * since_peak / DAY as f64).cos();
+
+            let weekday = ((ts + EPOCH_WEEKDAY_OFFSET) % WEEK) / DAY;
+            intensity *= if weekday < 5 {
+                1.0 + s.weekly_amplitude
+            } else {
+                1.0 - s.weekly_amplitude
+            };
+        }
+
+        if self.bursts.iter().any(|&(from, to)| (from..to).contains(&ts)) {
+            intensity *= self.burst_intensity;
+        }
+
+        intensity
+    }
+
+    fn accept<R: Rng + ?Sized>(&self, ts: u64, rng: &mut R) -> bool {
+        !self.is_modulated() || rng.random::<f64>() * self.max_intensity < self.intensity(ts)
+    }
+
+    /// Next timestamp, `None` once Poisson arrivals have passed the range end.
+    pub fn next_ts<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<u64> {
+        match self.arrival {
+            Arrival::Uniform => loop {
+                let span = self.end - self.start;
+                let ts = if span == 0 {
+                    self.end
+                } else {
+                    self.end - rng.random_range(0..span)
+                };
+                if self.accept(ts, rng) {
+                    return Some(ts);
+                }
+            },
+            Arrival::Poisson { .. } => loop {
+                if let Some(gaps) = &self.gaps {
+                    self.clock += gaps.sample(rng);
+                }
+                // Round up so that arrivals right after `start` stay inside
+                // `(start, end]`.
+                let ts = (self.clock.ceil() as u64).max(self.start + 1);
+                if ts > self.end {
+                    return None;
+                }
+                if self.accept(ts, rng) {
+                    return Some(ts);
+                }
+            },
+        }
+    }
+}
@@ -1,64 +1,133 @@
 use anyhow::{Context, Result};
 use rand::Rng;
-use std::time::{SystemTime, UNIX_EPOCH};
+use rand_chacha::ChaCha8Rng;
 
 use crate::model::Transfer;
 use crate::generator::config::TransferGenConfig;
+use crate::generator::errors::ConfigError;
 use crate::generator::address::rand_address;
+use crate::generator::pool::AddressPool;
+use crate::generator::price::{price_transfers, PricePath};
+use crate::generator::timing::Timeline;
 
 pub trait TransferGenerator {
     fn generate(&self, count: usize) -> Result<Vec<Transfer>>;
 }
 
 pub struct DefaultTransferGenerator {
-    pub config: TransferGenConfig,
+    config: TransferGenConfig,
 }
 
 impl DefaultTransferGenerator {
-    pub fn new(config: TransferGenConfig) -> Self {
-        Self { config }
+    pub fn new(config: TransferGenConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+
+    /// The validated configuration.
+    pub fn config(&self) -> &TransferGenConfig {
+        &self.config
+    }
+
+    /// Lazy sequence of transfers; bound it with `take`. With
+    /// [`Arrival::Poisson`](crate::generator::timing::Arrival::Poisson) it
+    /// ends at the range end.
+    ///
+    /// Prices follow the configured price process in emission order, so they
+    /// are only time-consistent for monotonic arrivals such as
+    /// [`Arrival::Poisson`](crate::generator::timing::Arrival::Poisson).
+    /// [`TransferGenConfig::sorted`] is ignored.
+    pub fn iter(&self) -> Result<TransferIter> {
+        TransferIter::new(&self.config, true)
     }
 }
 
 impl TransferGenerator for DefaultTransferGenerator {
     fn generate(&self, count: usize) -> Result<Vec<Transfer>> {
-        let mut rng = rand::rng();
-
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .context("Failed to get duration since UNIX_EPOCH")?
-            .as_secs();
-
-        let transfers = (0..count)
-            .map(|_| {
-                let from = rand_address(&mut rng);
-                let to = rand_address(&mut rng);
-
-                let amount = rng.random_range(self.config.min_amount..self.config.max_amount);
-                let usd_price = rng.random_range(self.config.min_price..self.config.max_price);
-
-                let ts = if self.config.max_age_secs == 0 {
-                    now
-                } else {
-                    now - rng.random_range(0..self.config.max_age_secs)
-                };
-
-                Transfer {
-                    ts,
-                    from,
-                    to,
-                    amount,
-                    usd_price,
-                }
-            })
-            .collect();
+        let mut iter = TransferIter::new(&self.config, false)?;
+        let mut transfers: Vec<Transfer> = iter.by_ref().take(count).collect();
+
+        if let Some(mut path) = self.config.price_path(iter.start)? {
+            price_transfers(&mut path, &mut transfers, &mut iter.rng);
+        }
+        if self.config.sorted {
+            transfers.sort_by_key(|t| t.ts);
+        }
 
         Ok(transfers)
     }
 }
 
+/// Iterator returned by [`DefaultTransferGenerator::iter`].
+pub struct TransferIter {
+    config: TransferGenConfig,
+    rng: ChaCha8Rng,
+    pool: Option<AddressPool>,
+    timeline: Timeline,
+    price_path: Option<PricePath>,
+    start: u64,
+}
+
+impl TransferIter {
+    fn new(config: &TransferGenConfig, inline_prices: bool) -> Result<Self> {
+        let mut rng = config.rng();
+        let (start, end) = config.time_range()?;
+        let pool = config
+            .pool
+            .clone()
+            .map(|config| AddressPool::new(config, &mut rng))
+            .transpose()
+            .context("Failed to build address pool")?;
+        let timeline = config.timeline(start, end, &mut rng)?;
+        let price_path = if inline_prices {
+            config.price_path(start)?
+        } else {
+            None
+        };
+
+        Ok(Self {
+            config: config.clone(),
+            rng,
+            pool,
+            timeline,
+            price_path,
+            start,
+        })
+    }
+}
+
+impl Iterator for TransferIter {
+    type Item = Transfer;
+
+    fn next(&mut self) -> Option<Transfer> {
+        let rng = &mut self.rng;
+
+        let (from, to) = match &self.pool {
+            Some(pool) => pool.pick_pair(rng),
+            None => (rand_address(rng), rand_address(rng)),
+        };
+
+        let amount = self.config.sample_amount(self.config.max_amount, rng);
+        let usd_price = rng.random_range(self.config.min_price..self.config.max_price);
+
+        let ts = self.timeline.next_ts(rng)?;
+        let usd_price = match &mut self.price_path {
+            Some(path) => path.price_at(ts, rng),
+            None => usd_price,
+        };
+
+        Some(Transfer {
+            ts,
+            from,
+            to,
+            amount,
+            usd_price,
+        })
+    }
+}
+
 pub fn generate_transfers(count: usize) -> Result<Vec<Transfer>> {
     let config = TransferGenConfig::default();
-    let generator = DefaultTransferGenerator::new(config);
+    let generator = DefaultTransferGenerator::new(config)?;
     generator.generate(count)
 }
@@ -0,0 +1,61 @@
+use std::io::Read;
+
+use ::csv::{ReaderBuilder, StringRecord, Trim};
+
+use crate::import::errors::{ImportError, RowError};
+use crate::import::options::ImportOptions;
+use crate::import::row::{parse_transfer, RawValue};
+use crate::import::ImportReport;
+
+/// Reads transfers from CSV with a header row naming the mapped columns.
+///
+/// Extra columns are ignored; a mapped column missing from the header fails
+/// the whole import.
+pub fn read_csv<R: Read>(reader: R, options: &ImportOptions) -> Result<ImportReport, ImportError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .trim(Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+
+    let header = reader.headers()?.clone();
+    let columns = &options.columns;
+    for column in [&columns.ts, &columns.from, &columns.to, &columns.amount, &columns.usd_price] {
+        if !header.iter().any(|h| h == column) {
+            return Err(ImportError::MissingColumn(column.clone()));
+        }
+    }
+
+    let mut report = ImportReport::default();
+    let mut record = StringRecord::new();
+
+    loop {
+        let line = reader.position().line();
+        match reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => {
+                let line = record.position().map_or(line, |p| p.line());
+                let field = |name: &str| {
+                    let index = header.iter().position(|h| h == name)?;
+                    record.get(index).filter(|v| !v.is_empty()).map(RawValue::Text)
+                };
+                match parse_transfer(line, options, field) {
+                    Ok(transfer) => report.transfers.push(transfer),
+                    Err(error) => report.reject(error, options.max_errors)?,
+                }
+            }
+            Err(error) if error.is_io_error() => return Err(error.into()),
+            Err(error) => {
+                let line = error.position().map_or(line, |p| p.line());
+                let error = RowError {
+                    line,
+                    column: None,
+                    message: error.to_string(),
+                };
+                report.reject(error, options.max_errors)?;
+            }
+        }
+    }
+
+    Ok(report)
+}
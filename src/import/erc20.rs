@@ -0,0 +1,284 @@
+//! Decoding of ERC-20 `Transfer(address,address,uint256)` event logs as
+//! returned by `eth_getLogs`.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::import::errors::{ImportError, RowError};
+use crate::import::ImportReport;
+use crate::model::{Address, AddressError, Amount, Transfer};
+
+/// `keccak256("Transfer(address,address,uint256)")`.
+pub const TRANSFER_TOPIC: &str =
+    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+/// A log in JSON-RPC form; quantities are `0x`-prefixed hex strings.
+///
+/// Fields a [`Transfer`] has no place for, such as `transactionHash` and
+/// `logIndex`, are ignored.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: String,
+    /// `None` for pending logs.
+    pub block_number: Option<String>,
+    /// Set when the log was dropped by a chain reorganisation.
+    #[serde(default)]
+    pub removed: bool,
+    /// Non-standard field some providers include; used before
+    /// [`BlockTimestamps`] when present.
+    pub block_timestamp: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Response<T> {
+    Rpc { result: T },
+    Bare(T),
+}
+
+impl<T> Response<T> {
+    fn into_inner(self) -> T {
+        match self {
+            Self::Rpc { result } | Self::Bare(result) => result,
+        }
+    }
+}
+
+/// Parses an `eth_getLogs` response, or the bare array of logs from its `result`.
+pub fn parse_logs(json: &str) -> Result<Vec<Log>, ImportError> {
+    Ok(serde_json::from_str::<Response<Vec<Log>>>(json)?.into_inner())
+}
+
+#[derive(Deserialize)]
+struct Block {
+    number: String,
+    timestamp: String,
+}
+
+/// Parses block timestamps from a batch of `eth_getBlockByNumber` responses,
+/// or a bare array of blocks.
+pub fn parse_block_timestamps(json: &str) -> Result<HashMap<u64, u64>, ImportError> {
+    let blocks: Vec<Response<Block>> = serde_json::from_str(json)?;
+
+    blocks
+        .into_iter()
+        .map(Response::into_inner)
+        .map(|block| {
+            let number = parse_quantity(&block.number);
+            let timestamp = parse_quantity(&block.timestamp);
+            number.zip(timestamp).ok_or_else(|| {
+                ImportError::InvalidQuantity(format!("{} / {}", block.number, block.timestamp))
+            })
+        })
+        .collect()
+}
+
+/// Source of block timestamps, e.g. a cache filled from `eth_getBlockByNumber`.
+pub trait BlockTimestamps {
+    fn block_timestamp(&self, block: u64) -> Option<u64>;
+}
+
+impl BlockTimestamps for HashMap<u64, u64> {
+    fn block_timestamp(&self, block: u64) -> Option<u64> {
+        self.get(&block).copied()
+    }
+}
+
+/// Source of `usd_price` for a token at a timestamp.
+pub trait PriceLookup {
+    fn usd_price(&self, token: Address, ts: u64) -> Option<f64>;
+}
+
+/// The same price for every token and time.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedPrice(pub f64);
+
+impl PriceLookup for FixedPrice {
+    fn usd_price(&self, _token: Address, _ts: u64) -> Option<f64> {
+        Some(self.0)
+    }
+}
+
+/// Step-wise price series: each price holds until the next point.
+#[derive(Debug, Clone, Default)]
+pub struct PriceTable {
+    points: Vec<(u64, f64)>,
+}
+
+impl PriceTable {
+    pub fn new(mut points: Vec<(u64, f64)>) -> Self {
+        points.sort_by_key(|&(ts, _)| ts);
+        Self { points }
+    }
+}
+
+impl PriceLookup for PriceTable {
+    /// Latest price at or before `ts`; `None` before the first point.
+    fn usd_price(&self, _token: Address, ts: u64) -> Option<f64> {
+        let after = self.points.partition_point(|&(point, _)| point <= ts);
+        after.checked_sub(1).map(|i| self.points[i].1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Erc20Config {
+    /// Token decimals; amounts are divided by `10^decimals`.
+    pub decimals: u8,
+    /// Decode only logs emitted by this contract.
+    pub token: Option<Address>,
+    /// Fail with [`ImportError::TooManyErrors`] past this many bad logs.
+    pub max_errors: Option<usize>,
+}
+
+impl Default for Erc20Config {
+    fn default() -> Self {
+        Self {
+            decimals: 18,
+            token: None,
+            max_errors: None,
+        }
+    }
+}
+
+/// Turns `Transfer` logs into transfers with checksummed addresses.
+///
+/// Other events, logs from other contracts and removed logs are skipped.
+/// Malformed `Transfer` logs and those without a timestamp or price are
+/// reported with their 1-based position in `logs`.
+pub fn decode_logs(
+    logs: &[Log],
+    config: &Erc20Config,
+    timestamps: &(impl BlockTimestamps + ?Sized),
+    prices: &(impl PriceLookup + ?Sized),
+) -> Result<ImportReport, ImportError> {
+    let mut report = ImportReport::default();
+
+    for (i, log) in logs.iter().enumerate() {
+        let is_transfer = log
+            .topics
+            .first()
+            .is_some_and(|topic| topic.eq_ignore_ascii_case(TRANSFER_TOPIC));
+        let from_token = config
+            .token
+            .is_none_or(|token| log.address.parse::<Address>() == Ok(token));
+        if !is_transfer || !from_token || log.removed {
+            continue;
+        }
+
+        match decode_log(log, config, timestamps, prices) {
+            Ok(transfer) => report.transfers.push(transfer),
+            Err((column, message)) => {
+                let error = RowError {
+                    line: i as u64 + 1,
+                    column: Some(column.to_string()),
+                    message,
+                };
+                report.reject(error, config.max_errors)?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+type LogError = (&'static str, String);
+
+fn decode_log(
+    log: &Log,
+    config: &Erc20Config,
+    timestamps: &(impl BlockTimestamps + ?Sized),
+    prices: &(impl PriceLookup + ?Sized),
+) -> Result<Transfer, LogError> {
+    if log.topics.len() != 3 {
+        let message = format!(
+            "expected 3 topics, got {}; ERC-721 transfers index the token id",
+            log.topics.len()
+        );
+        return Err(("topics", message));
+    }
+
+    let from = topic_address(&log.topics[1]).map_err(|e| ("topics", e))?;
+    let to = topic_address(&log.topics[2]).map_err(|e| ("topics", e))?;
+    let token: Address = log
+        .address
+        .parse()
+        .map_err(|e: AddressError| ("address", e.to_string()))?;
+    let amount = parse_amount(&log.data, config.decimals).map_err(|e| ("data", e))?;
+    let ts = log_timestamp(log, timestamps)?;
+    let usd_price = prices
+        .usd_price(token, ts)
+        .ok_or_else(|| ("usd_price", format!("no price for {} at {}", token, ts)))?;
+
+    Ok(Transfer {
+        ts,
+        from,
+        to,
+        amount,
+        usd_price,
+    })
+}
+
+fn log_timestamp(
+    log: &Log,
+    timestamps: &(impl BlockTimestamps + ?Sized),
+) -> Result<u64, LogError> {
+    if let Some(ts) = &log.block_timestamp {
+        return parse_quantity(ts)
+            .ok_or_else(|| ("blockTimestamp", format!("invalid quantity '{}'", ts)));
+    }
+
+    let block = log
+        .block_number
+        .as_deref()
+        .ok_or_else(|| ("blockNumber", "pending log has no block".to_string()))?;
+    let block = parse_quantity(block)
+        .ok_or_else(|| ("blockNumber", format!("invalid quantity '{}'", block)))?;
+
+    timestamps
+        .block_timestamp(block)
+        .ok_or_else(|| ("blockNumber", format!("no timestamp for block {}", block)))
+}
+
+fn parse_quantity(s: &str) -> Option<u64> {
+    let digits = s.strip_prefix("0x")?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, String> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    hex::decode(digits).map_err(|e| format!("invalid hex '{}': {}", s, e))
+}
+
+/// Address from a 32-byte topic with 12 bytes of zero padding.
+fn topic_address(topic: &str) -> Result<Address, String> {
+    let bytes = decode_hex(topic)?;
+    match bytes.split_at_checked(12) {
+        Some((padding, address)) if padding.iter().all(|&b| b == 0) => {
+            let address: [u8; 20] = address
+                .try_into()
+                .map_err(|_| format!("topic '{}' is not a padded address", topic))?;
+            Ok(Address::from_bytes(address))
+        }
+        _ => Err(format!("topic '{}' is not a padded address", topic)),
+    }
+}
+
+/// `uint256` data word scaled down by `10^decimals`.
+fn parse_amount(data: &str, decimals: u8) -> Result<Amount, String> {
+    let bytes = decode_hex(data)?;
+    let Ok(word) = <[u8; 32]>::try_from(bytes.as_slice()) else {
+        return Err(format!("expected a 32-byte uint256, got {} bytes", bytes.len()));
+    };
+
+    let high = u128::from_be_bytes(word[..16].try_into().expect("16 bytes"));
+    let low = u128::from_be_bytes(word[16..].try_into().expect("16 bytes"));
+    let out_of_range = || format!("value {} exceeds the Decimal128(18) range", data);
+    if high != 0 {
+        return Err(out_of_range());
+    }
+    Amount::from_token_value(low, decimals).ok_or_else(out_of_range)
+}
@@ -0,0 +1,40 @@
+use std::fmt;
+
+use thiserror::Error;
+
+/// A row that could not be turned into a transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// 1-based line in the source counting the CSV header, or position in
+    /// the log array for decoded ERC-20 logs.
+    pub line: u64,
+    /// Source column the problem was found in, if any.
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(f, "line {}, column '{}': {}", self.line, column, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// Failures that abort a whole import.
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("CSV error: {0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid hex quantity {0}")]
+    InvalidQuantity(String),
+    #[error("Column '{0}' not found in the CSV header")]
+    MissingColumn(String),
+    #[error("More than {limit} invalid rows, last: {}", errors.last().map(ToString::to_string).unwrap_or_default())]
+    TooManyErrors { limit: usize, errors: Vec<RowError> },
+}
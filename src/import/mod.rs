@@ -0,0 +1,42 @@
+//! Readers that turn exported chain data into [`Transfer`](crate::model::Transfer)s.
+//!
+//! CSV and NDJSON files are supported. Columns are mapped by name through
+//! [`ColumnMapping`] and timestamps may be seconds, milliseconds or ISO-8601.
+//! ERC-20 `Transfer` logs from `eth_getLogs` are decoded by [`erc20`].
+//! Malformed rows do not abort an import; they are collected in
+//! [`ImportReport::errors`] with their line numbers.
+
+pub mod csv;
+pub mod erc20;
+pub mod errors;
+pub mod ndjson;
+pub mod options;
+mod row;
+
+pub use csv::read_csv;
+pub use errors::{ImportError, RowError};
+pub use ndjson::read_ndjson;
+pub use options::{ColumnMapping, ImportOptions, TimestampFormat};
+
+use crate::model::Transfer;
+
+/// Transfers read from a source together with the rows that were rejected.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub transfers: Vec<Transfer>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    /// Records a rejected row, failing once more than `max_errors` are seen.
+    pub(crate) fn reject(&mut self, error: RowError, max_errors: Option<usize>) -> Result<(), ImportError> {
+        self.errors.push(error);
+        match max_errors {
+            Some(limit) if self.errors.len() > limit => Err(ImportError::TooManyErrors {
+                limit,
+                errors: std::mem::take(&mut self.errors),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::BufRead;
+
+use serde_json::Value;
+
+use crate::import::errors::{ImportError, RowError};
+use crate::import::options::ImportOptions;
+use crate::import::row::{parse_transfer, RawValue};
+use crate::import::ImportReport;
+
+/// Reads transfers from newline-delimited JSON objects; blank lines are skipped.
+///
+/// Mapped fields may be JSON numbers or strings, so amounts exported as
+/// decimal strings are accepted.
+pub fn read_ndjson<R: BufRead>(reader: R, options: &ImportOptions) -> Result<ImportReport, ImportError> {
+    let mut report = ImportReport::default();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i as u64 + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let object = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Object(object)) => object,
+            Ok(_) => {
+                report.reject(row_error(line_no, "expected a JSON object"), options.max_errors)?;
+                continue;
+            }
+            Err(error) => {
+                report.reject(row_error(line_no, &error.to_string()), options.max_errors)?;
+                continue;
+            }
+        };
+
+        let field = |name: &str| {
+            object.get(name).and_then(|value| match value {
+                Value::Null => None,
+                Value::String(s) => Some(RawValue::Text(s.trim())),
+                Value::Number(n) => Some(RawValue::Number(n)),
+                _ => Some(RawValue::Other),
+            })
+        };
+        match parse_transfer(line_no, options, field) {
+            Ok(transfer) => report.transfers.push(transfer),
+            Err(error) => report.reject(error, options.max_errors)?,
+        }
+    }
+
+    Ok(report)
+}
+
+fn row_error(line: u64, message: &str) -> RowError {
+    RowError {
+        line,
+        column: None,
+        message: message.to_string(),
+    }
+}
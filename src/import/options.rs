@@ -0,0 +1,72 @@
+use std::str::FromStr;
+
+use anyhow::bail;
+
+/// Source column (CSV header or JSON key) for each [`Transfer`](crate::model::Transfer) field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+    pub ts: String,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub usd_price: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            ts: "ts".to_string(),
+            from: "from".to_string(),
+            to: "to".to_string(),
+            amount: "amount".to_string(),
+            usd_price: "usd_price".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampFormat {
+    /// Integers of at least 10^11 are milliseconds, smaller ones seconds;
+    /// anything else is parsed as ISO-8601.
+    #[default]
+    Auto,
+    Seconds,
+    Millis,
+    /// RFC 3339, or a date-time without offset taken as UTC.
+    Iso8601,
+}
+
+impl FromStr for TimestampFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "auto" => Self::Auto,
+            "secs" | "seconds" => Self::Seconds,
+            "millis" | "ms" => Self::Millis,
+            "iso8601" | "iso" | "rfc3339" => Self::Iso8601,
+            _ => bail!("Unknown timestamp format '{}', expected auto, secs, millis or iso8601", s),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub columns: ColumnMapping,
+    pub timestamp: TimestampFormat,
+    /// CSV field delimiter.
+    pub delimiter: u8,
+    /// Abort once more rows than this are rejected; `None` never aborts.
+    pub max_errors: Option<usize>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            columns: ColumnMapping::default(),
+            timestamp: TimestampFormat::default(),
+            delimiter: b',',
+            max_errors: None,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, NaiveDateTime};
+
+use crate::import::errors::RowError;
+use crate::import::options::{ImportOptions, TimestampFormat};
+use crate::model::{Address, AddressError, Amount, Transfer};
+
+/// Integer timestamps from this value on are taken as milliseconds by
+/// [`TimestampFormat::Auto`]; it is year 5138 in seconds and 1973 in millis.
+const MILLIS_THRESHOLD: u64 = 100_000_000_000;
+
+const NAIVE_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// A single field as found in the source.
+pub(crate) enum RawValue<'a> {
+    Text(&'a str),
+    Number(&'a serde_json::Number),
+    /// Booleans, arrays, objects and nulls.
+    Other,
+}
+
+/// Builds a transfer from the fields `field` looks up by source column.
+pub(crate) fn parse_transfer<'a>(
+    line: u64,
+    options: &ImportOptions,
+    field: impl Fn(&str) -> Option<RawValue<'a>>,
+) -> Result<Transfer, RowError> {
+    let columns = &options.columns;
+    let ts_format = options.timestamp;
+
+    Ok(Transfer {
+        ts: parse_column(line, &columns.ts, &field, |v| parse_ts(v, ts_format))?,
+        from: parse_column(line, &columns.from, &field, parse_address)?,
+        to: parse_column(line, &columns.to, &field, parse_address)?,
+        amount: parse_column(line, &columns.amount, &field, parse_amount)?,
+        usd_price: parse_column(line, &columns.usd_price, &field, parse_price)?,
+    })
+}
+
+fn parse_column<'a, T>(
+    line: u64,
+    column: &str,
+    field: &impl Fn(&str) -> Option<RawValue<'a>>,
+    parse: impl FnOnce(RawValue<'a>) -> Result<T, String>,
+) -> Result<T, RowError> {
+    field(column)
+        .ok_or_else(|| "missing value".to_string())
+        .and_then(parse)
+        .map_err(|message| row_error(line, column, message))
+}
+
+fn row_error(line: u64, column: &str, message: String) -> RowError {
+    RowError {
+        line,
+        column: Some(column.to_string()),
+        message,
+    }
+}
+
+fn parse_ts(value: RawValue<'_>, format: TimestampFormat) -> Result<u64, String> {
+    match (value, format) {
+        (RawValue::Number(_), TimestampFormat::Iso8601) => {
+            Err("expected an ISO-8601 string, got a number".to_string())
+        }
+        (RawValue::Number(n), format) => n
+            .as_u64()
+            .map(|v| integer_ts(v, format))
+            .ok_or_else(|| format!("expected a non-negative integer timestamp, got {}", n)),
+        (RawValue::Text(s), TimestampFormat::Iso8601) => parse_iso(s),
+        (RawValue::Text(s), TimestampFormat::Auto) => match s.parse::<u64>() {
+            Ok(v) => Ok(integer_ts(v, format)),
+            Err(_) => parse_iso(s),
+        },
+        (RawValue::Text(s), format) => s
+            .parse::<u64>()
+            .map(|v| integer_ts(v, format))
+            .map_err(|_| format!("expected a non-negative integer timestamp, got '{}'", s)),
+        (RawValue::Other, _) => Err("expected a timestamp".to_string()),
+    }
+}
+
+fn integer_ts(value: u64, format: TimestampFormat) -> u64 {
+    match format {
+        TimestampFormat::Millis => value / 1_000,
+        TimestampFormat::Auto if value >= MILLIS_THRESHOLD => value / 1_000,
+        _ => value,
+    }
+}
+
+fn parse_iso(s: &str) -> Result<u64, String> {
+    let secs = DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.timestamp())
+        .or_else(|_| {
+            NAIVE_FORMATS
+                .iter()
+                .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+                .map(|dt| dt.and_utc().timestamp())
+                .ok_or(())
+        })
+        .map_err(|_| format!("invalid ISO-8601 timestamp '{}'", s))?;
+
+    u64::try_from(secs).map_err(|_| format!("timestamp '{}' is before the Unix epoch", s))
+}
+
+fn parse_address(value: RawValue<'_>) -> Result<Address, String> {
+    match value {
+        RawValue::Text(s) => s.parse().map_err(|e: AddressError| e.to_string()),
+        _ => Err("expected an address string".to_string()),
+    }
+}
+
+/// Exact for plain decimals; exponent notation goes through `f64`.
+fn parse_amount(value: RawValue<'_>) -> Result<Amount, String> {
+    let text = match value {
+        RawValue::Number(n) => n.to_string(),
+        RawValue::Text(s) => s.to_string(),
+        RawValue::Other => return Err("expected a number".to_string()),
+    };
+    let amount = text
+        .parse::<Amount>()
+        .or_else(|e| text.parse::<f64>().ok().and_then(Amount::from_f64).ok_or(e))
+        .map_err(|e| e.to_string())?;
+
+    if amount.is_negative() {
+        return Err(format!("expected a non-negative amount, got {}", amount));
+    }
+    Ok(amount)
+}
+
+fn parse_price(value: RawValue<'_>) -> Result<f64, String> {
+    let price = match value {
+        RawValue::Number(n) => n.as_f64(),
+        RawValue::Text(s) => s.parse::<f64>().ok(),
+        RawValue::Other => None,
+    }
+    .ok_or_else(|| "expected a number".to_string())?;
+
+    if price.is_finite() && price >= 0.0 {
+        Ok(price)
+    } else {
+        Err(format!("expected a finite non-negative number, got {}", price))
+    }
+}
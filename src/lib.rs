@@ -1,3 +1,6 @@
+pub mod export;
 pub mod generator;
+pub mod import;
 pub mod pipeline;
 pub mod model;
+pub mod storage;
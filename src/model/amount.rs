@@ -0,0 +1,258 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+/// Signed fixed-point token amount with [`Amount::DECIMALS`] fractional digits.
+///
+/// Sums and balances are exact. The raw value has the layout of a ClickHouse
+/// `Decimal128(18)`, which is how it is stored; human-readable formats use
+/// the decimal string so no precision is lost in JSON.
+///
+/// Values are bounded by [`Amount::MIN`] and [`Amount::MAX`], the 38 digits of
+/// a `Decimal128`. The arithmetic operators panic outside that range; use the
+/// `checked_*` methods where inputs are untrusted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(i128);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AmountError {
+    #[error("Invalid amount '{0}'")]
+    Invalid(String),
+    #[error("Amount '{0}' has more than 18 fractional digits")]
+    TooPrecise(String),
+    #[error("Amount '{0}' is out of the Decimal128(18) range")]
+    Overflow(String),
+}
+
+impl Amount {
+    pub const DECIMALS: u32 = 18;
+    pub const ZERO: Self = Self(0);
+    /// Largest `Decimal128(18)`, `10^20 - 10^-18`.
+    pub const MAX: Self = Self(Self::RAW_MAX);
+    pub const MIN: Self = Self(-Self::RAW_MAX);
+
+    const RAW_MAX: i128 = 10i128.pow(38) - 1;
+
+    const SCALE: i128 = 10i128.pow(Self::DECIMALS);
+
+    /// Wraps a value already scaled by `10^18`; the caller keeps it within
+    /// [`MIN`](Self::MIN)..=[`MAX`](Self::MAX).
+    pub const fn from_raw(raw: i128) -> Self {
+        Self(raw)
+    }
+
+    /// `raw` if it fits `Decimal128(18)`.
+    fn bounded(raw: i128) -> Option<Self> {
+        (-Self::RAW_MAX..=Self::RAW_MAX).contains(&raw).then_some(Self(raw))
+    }
+
+    pub const fn raw(self) -> i128 {
+        self.0
+    }
+
+    /// Whole units, e.g. `from_units(5)` is `5.0`.
+    pub const fn from_units(units: i64) -> Self {
+        Self(units as i128 * Self::SCALE)
+    }
+
+    /// Integer token value with `decimals` places, as in ERC-20 `uint256`
+    /// amounts. Digits beyond 18 decimals are truncated.
+    pub fn from_token_value(value: u128, decimals: u8) -> Option<Self> {
+        let decimals = u32::from(decimals);
+        let raw = if decimals <= Self::DECIMALS {
+            value.checked_mul(10u128.pow(Self::DECIMALS - decimals))?
+        } else {
+            value / 10u128.checked_pow(decimals - Self::DECIMALS).unwrap_or(u128::MAX)
+        };
+        i128::try_from(raw).ok().and_then(Self::bounded)
+    }
+
+    /// Amount with the shortest decimal representation of `value`, so `0.1`
+    /// becomes exactly `0.1`; digits beyond 18 decimals are rounded. `None`
+    /// for NaN, infinities and values out of range.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        match value.to_string().parse() {
+            Ok(amount) => Some(amount),
+            Err(AmountError::TooPrecise(_)) => {
+                Self::bounded((value * Self::SCALE as f64).round() as i128)
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Lossy conversion for display, averages and plotting.
+    pub fn to_f64(self) -> f64 {
+        let units = self.0 / Self::SCALE;
+        let fraction = self.0 % Self::SCALE;
+        units as f64 + fraction as f64 / Self::SCALE as f64
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).and_then(Self::bounded)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).and_then(Self::bounded)
+    }
+
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0).clamp(-Self::RAW_MAX, Self::RAW_MAX))
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0).clamp(-Self::RAW_MAX, Self::RAW_MAX))
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+}
+
+impl Add for Amount {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("Amount overflow")
+    }
+}
+
+impl Sub for Amount {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other).expect("Amount overflow")
+    }
+}
+
+impl AddAssign for Amount {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Amount {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Amount {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.checked_neg().expect("Amount overflow"))
+    }
+}
+
+impl Sum for Amount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for Amount {
+    /// Shortest exact decimal, e.g. `12.5`, `3`, `-0.000001`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let scale = Self::SCALE as u128;
+        let (units, fraction) = (abs / scale, abs % scale);
+
+        if fraction == 0 {
+            return write!(f, "{}{}", sign, units);
+        }
+        let digits = format!("{:018}", fraction);
+        write!(f, "{}{}.{}", sign, units, digits.trim_end_matches('0'))
+    }
+}
+
+impl FromStr for Amount {
+    type Err = AmountError;
+
+    /// Plain decimal notation with an optional sign; no exponents.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AmountError::Invalid(s.to_string());
+
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (units, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if units.is_empty() && fraction.is_empty() || !is_digits(units) || !is_digits(fraction) {
+            return Err(invalid());
+        }
+        if fraction.len() > Self::DECIMALS as usize {
+            return Err(AmountError::TooPrecise(s.to_string()));
+        }
+
+        let overflow = || AmountError::Overflow(s.to_string());
+        let units: i128 = if units.is_empty() { 0 } else { units.parse().map_err(|_| overflow())? };
+        let padded = format!("{:0<18}", fraction);
+        let fraction: i128 = padded.parse().map_err(|_| invalid())?;
+
+        let raw = units
+            .checked_mul(Self::SCALE)
+            .and_then(|r| r.checked_add(fraction))
+            .and_then(Self::bounded)
+            .ok_or_else(overflow)?;
+        Ok(Self(if negative { -raw.0 } else { raw.0 }))
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_i128(self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(AmountVisitor)
+        } else {
+            i128::deserialize(deserializer).map(Self)
+        }
+    }
+}
+
+/// Accepts decimal strings, integers and, lossily, floats.
+struct AmountVisitor;
+
+impl Visitor<'_> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a decimal amount")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Amount, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Amount, E> {
+        Ok(Amount::from_units(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Amount, E> {
+        Amount::from_token_value(v.into(), 0)
+            .ok_or_else(|| E::custom(AmountError::Overflow(v.to_string())))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Amount, E> {
+        Amount::from_f64(v).ok_or_else(|| E::custom(AmountError::Overflow(v.to_string())))
+    }
+}
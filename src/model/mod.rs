@@ -0,0 +1,33 @@
+mod address;
+mod amount;
+
+use std::collections::BTreeMap;
+
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+pub use address::{Address, AddressError};
+pub use amount::{Amount, AmountError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct Transfer {
+    pub ts: u64,
+    pub from: Address,
+    pub to: Address,
+    pub amount: Amount,
+    pub usd_price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserStats {
+    pub address: Address,
+    pub total_volume: Amount,
+    /// Volume-weighted USD prices; ratios stay `f64`.
+    pub avg_buy_price: f64,
+    pub avg_sell_price: f64,
+    pub max_balance: Amount,
+    /// Max balance per window label (`1h`, `24h`, ...). Each covers the
+    /// trailing period that ends at the latest processed transfer, including
+    /// the balance carried into the period.
+    pub max_balance_windows: BTreeMap<String, Amount>,
+}
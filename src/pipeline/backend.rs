@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use crate::pipeline::config::PipelineConfig;
+use crate::pipeline::pipeline::calculate_user_stats_with;
+use crate::storage::Storage;
+
+/// Computes [`UserStats`](crate::model::UserStats) from the stored transfers
+/// and saves them.
+///
+/// Backends agree on the result as long as transfers with equal `ts` are
+/// applied in the same order.
+#[async_trait]
+pub trait StatsBackend: Send + Sync {
+    async fn compute_stats(&self, config: &PipelineConfig) -> Result<()>;
+}
+
+/// Loads every transfer into memory and runs [`calculate_user_stats_with`].
+pub struct InProcessStatsBackend {
+    storage: Arc<dyn Storage>,
+}
+
+impl InProcessStatsBackend {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait]
+impl StatsBackend for InProcessStatsBackend {
+    async fn compute_stats(&self, config: &PipelineConfig) -> Result<()> {
+        let transfers = self
+            .storage
+            .get_transfers()
+            .await
+            .context("Failed to load transfers")?;
+        let stats = calculate_user_stats_with(&transfers, config)?;
+        self.storage.save_stats(&stats).await.context("Failed to save stats")?;
+        Ok(())
+    }
+}
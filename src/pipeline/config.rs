@@ -0,0 +1,119 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+
+use crate::pipeline::engine::LatePolicy;
+
+/// Trailing period for a windowed max balance, e.g. `5m`, `24h`, `90d`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct BalanceWindow {
+    label: String,
+    secs: u64,
+}
+
+impl BalanceWindow {
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn secs(&self) -> u64 {
+        self.secs
+    }
+
+    /// Sort key ordering window labels by length; labels that do not parse
+    /// go last.
+    pub fn label_order(label: &str) -> (u64, &str) {
+        (label.parse::<Self>().map_or(u64::MAX, |w| w.secs), label)
+    }
+}
+
+impl FromStr for BalanceWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let label = s.trim();
+        let split = label
+            .find(|c: char| !c.is_ascii_digit())
+            .with_context(|| format!("Window '{}' has no unit (s, m, h, d, w)", label))?;
+        let (value, unit) = label.split_at(split);
+
+        let value: u64 = value
+            .parse()
+            .with_context(|| format!("Window '{}' has no numeric length", label))?;
+        let unit_secs = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3_600,
+            "d" => 86_400,
+            "w" => 7 * 86_400,
+            _ => bail!("Window '{}' has unknown unit '{}'", label, unit),
+        };
+        let secs = value
+            .checked_mul(unit_secs)
+            .with_context(|| format!("Window '{}' is too long", label))?;
+        if secs == 0 {
+            bail!("Window '{}' must be longer than zero", label);
+        }
+
+        Ok(Self {
+            label: label.to_string(),
+            secs,
+        })
+    }
+}
+
+impl TryFrom<String> for BalanceWindow {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for BalanceWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipelineConfig {
+    pub windows: Vec<BalanceWindow>,
+    pub late_policy: LatePolicy,
+    /// Worker threads for batch calculation: `1` runs on the calling thread,
+    /// `0` uses one per core. Anything but `1` needs the `parallel` feature.
+    pub threads: usize,
+}
+
+impl PipelineConfig {
+    /// Fails on windows that repeat a label or a length, which would collapse
+    /// into one entry of [`UserStats::max_balance_windows`].
+    ///
+    /// [`UserStats::max_balance_windows`]: crate::model::UserStats::max_balance_windows
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (i, window) in self.windows.iter().enumerate() {
+            if let Some(other) = self.windows[..i].iter().find(|w| w.secs == window.secs) {
+                bail!("Window '{}' duplicates window '{}'", window, other);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            windows: vec![
+                BalanceWindow { label: "1h".to_string(), secs: 3_600 },
+                BalanceWindow { label: "24h".to_string(), secs: 86_400 },
+                BalanceWindow { label: "7d".to_string(), secs: 7 * 86_400 },
+            ],
+            late_policy: LatePolicy::default(),
+            threads: 1,
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::model::{Address, Amount, Transfer, UserStats};
+use crate::pipeline::config::{BalanceWindow, PipelineConfig};
+
+/// What [`StatsEngine`] does with a transfer older than its watermark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatePolicy {
+    /// Fail the push and leave the engine untouched.
+    #[default]
+    Reject,
+    /// Ignore the transfer and count it in [`StatsEngine::skipped`].
+    Skip,
+    /// Apply the transfer as if it happened at the watermark.
+    Clamp,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct WeightedPrice {
+    sum_weighted: f64,
+    sum_amount: Amount,
+}
+
+impl WeightedPrice {
+    fn add(&mut self, price: f64, amount: Amount) -> Result<()> {
+        self.sum_weighted += price * amount.to_f64();
+        self.sum_amount = checked(self.sum_amount.checked_add(amount))?;
+        Ok(())
+    }
+
+    fn average(&self) -> Result<f64> {
+        if self.sum_amount <= Amount::ZERO {
+            return Ok(0.0);
+        }
+        if !self.sum_weighted.is_finite() {
+            return Err(anyhow::anyhow!(
+                "Invalid arithmetic result: sum_weighted={}, sum_amount={}",
+                self.sum_weighted,
+                self.sum_amount
+            ))
+                .context("Arithmetic overflow in weighted average calculation");
+        }
+        Ok(self.sum_weighted / self.sum_amount.to_f64())
+    }
+}
+
+fn checked(amount: Option<Amount>) -> Result<Amount> {
+    amount.context("Amount overflows Decimal128(18)")
+}
+
+/// Sliding maximum of a balance over a trailing window.
+///
+/// Each entry is a balance together with the timestamp at which it was
+/// replaced (`u64::MAX` while it is still current). Balances stay in
+/// decreasing order, so the front holds the maximum over every entry that was
+/// still held after the window start.
+#[derive(Debug, Default)]
+struct WindowMax {
+    window: u64,
+    entries: VecDeque<(u64, Amount)>,
+}
+
+impl WindowMax {
+    fn new(window: u64) -> Self {
+        Self {
+            window,
+            entries: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, ts: u64, balance: Amount) {
+        if let Some(last) = self.entries.back_mut() {
+            last.0 = ts;
+        }
+        while self.entries.back().is_some_and(|&(_, b)| b <= balance) {
+            self.entries.pop_back();
+        }
+        self.entries.push_back((u64::MAX, balance));
+
+        let cutoff = ts.saturating_sub(self.window);
+        while self.entries.front().is_some_and(|&(until, _)| until <= cutoff) {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Largest balance held during the window ending at `watermark`, never
+    /// below zero.
+    fn max(&self, watermark: u64) -> Amount {
+        let cutoff = watermark.saturating_sub(self.window);
+        let first = self.entries.partition_point(|&(until, _)| until <= cutoff);
+
+        self.entries
+            .get(first)
+            .map_or(Amount::ZERO, |&(_, balance)| balance.max(Amount::ZERO))
+    }
+}
+
+/// Largest balance of a timestamp-ordered `history` held during the `window`
+/// seconds ending at `end`, including the balance carried into them, or
+/// zero; one pass over the history.
+pub fn window_max(history: &[(u64, Amount)], window: u64, end: u64) -> Amount {
+    let mut max = WindowMax::new(window);
+    for &(ts, balance) in history {
+        max.push(ts, balance);
+    }
+    max.max(end)
+}
+
+/// Running totals a transfer changes, checked before any account is touched.
+#[derive(Debug, Default, Clone, Copy)]
+struct Totals {
+    balance: Amount,
+    total_volume: Amount,
+    bought: Amount,
+    sold: Amount,
+}
+
+impl Totals {
+    fn send(self, amount: Amount) -> Result<Self> {
+        Ok(Self {
+            balance: checked(self.balance.checked_sub(amount))?,
+            total_volume: checked(self.total_volume.checked_add(amount))?,
+            sold: checked(self.sold.checked_add(amount))?,
+            ..self
+        })
+    }
+
+    fn receive(self, amount: Amount) -> Result<Self> {
+        Ok(Self {
+            balance: checked(self.balance.checked_add(amount))?,
+            total_volume: checked(self.total_volume.checked_add(amount))?,
+            bought: checked(self.bought.checked_add(amount))?,
+            ..self
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AccountState {
+    balance: Amount,
+    max_balance: Amount,
+    total_volume: Amount,
+    buys: WeightedPrice,
+    sells: WeightedPrice,
+    windows: Vec<WindowMax>,
+}
+
+impl AccountState {
+    pub(crate) fn new(windows: &[BalanceWindow]) -> Self {
+        Self {
+            balance: Amount::ZERO,
+            max_balance: Amount::ZERO,
+            total_volume: Amount::ZERO,
+            buys: WeightedPrice::default(),
+            sells: WeightedPrice::default(),
+            windows: windows.iter().map(|w| WindowMax::new(w.secs())).collect(),
+        }
+    }
+
+    fn apply(&mut self, ts: u64, balance: Option<Amount>, amount: Amount) -> Result<()> {
+        self.balance = checked(balance)?;
+        self.max_balance = self.max_balance.max(self.balance);
+        for window in &mut self.windows {
+            window.push(ts, self.balance);
+        }
+        self.total_volume = checked(self.total_volume.checked_add(amount))?;
+        Ok(())
+    }
+
+    pub(crate) fn send(&mut self, ts: u64, amount: Amount, price: f64) -> Result<()> {
+        self.apply(ts, self.balance.checked_sub(amount), amount)?;
+        self.sells.add(price, amount)
+    }
+
+    pub(crate) fn receive(&mut self, ts: u64, amount: Amount, price: f64) -> Result<()> {
+        self.apply(ts, self.balance.checked_add(amount), amount)?;
+        self.buys.add(price, amount)
+    }
+
+    fn totals(&self) -> Totals {
+        Totals {
+            balance: self.balance,
+            total_volume: self.total_volume,
+            bought: self.buys.sum_amount,
+            sold: self.sells.sum_amount,
+        }
+    }
+
+    pub(crate) fn to_stats(
+        &self,
+        address: Address,
+        watermark: u64,
+        windows: &[BalanceWindow],
+    ) -> Result<UserStats> {
+        let avg_buy_price = self.buys.average()
+            .context("Failed to calculate average buy price")?;
+        let avg_sell_price = self.sells.average()
+            .context("Failed to calculate average sell price")?;
+
+        Ok(UserStats {
+            address,
+            total_volume: self.total_volume,
+            avg_buy_price,
+            avg_sell_price,
+            max_balance: self.max_balance,
+            max_balance_windows: windows
+                .iter()
+                .zip(&self.windows)
+                .map(|(w, max)| (w.label().to_string(), max.max(watermark)))
+                .collect(),
+        })
+    }
+}
+
+/// Incremental calculator of [`UserStats`].
+///
+/// Transfers are applied in timestamp order; the latest applied timestamp is
+/// the watermark. Windowed maxima cover the balance held during the trailing
+/// window that ends at the watermark.
+#[derive(Debug, Default)]
+pub struct StatsEngine {
+    config: PipelineConfig,
+    watermark: Option<u64>,
+    accounts: HashMap<Address, AccountState>,
+    skipped: usize,
+}
+
+impl StatsEngine {
+    pub fn new(config: PipelineConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn watermark(&self) -> Option<u64> {
+        self.watermark
+    }
+
+    /// Number of late transfers dropped under [`LatePolicy::Skip`].
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Applies one transfer. A rejected late transfer or an amount that would
+    /// overflow either account fails the push without changing any state.
+    pub fn push(&mut self, transfer: &Transfer) -> Result<()> {
+        let mut ts = transfer.ts;
+
+        if let Some(watermark) = self.watermark.filter(|&w| ts < w) {
+            match self.config.late_policy {
+                LatePolicy::Reject => bail!(
+                    "Late transfer at ts={} behind watermark {}",
+                    ts,
+                    watermark
+                ),
+                LatePolicy::Skip => {
+                    self.skipped += 1;
+                    return Ok(());
+                }
+                LatePolicy::Clamp => ts = watermark,
+            }
+        }
+
+        let totals = |address| self.accounts.get(&address).map(AccountState::totals);
+        let sent = totals(transfer.from).unwrap_or_default().send(transfer.amount)?;
+        let receiver = if transfer.from == transfer.to { Some(sent) } else { totals(transfer.to) };
+        receiver.unwrap_or_default().receive(transfer.amount)?;
+
+        self.watermark = Some(ts);
+        let windows = &self.config.windows;
+
+        self.accounts
+            .entry(transfer.from)
+            .or_insert_with(|| AccountState::new(windows))
+            .send(ts, transfer.amount, transfer.usd_price)?;
+
+        self.accounts
+            .entry(transfer.to)
+            .or_insert_with(|| AccountState::new(windows))
+            .receive(ts, transfer.amount, transfer.usd_price)?;
+
+        Ok(())
+    }
+
+    /// Applies a chunk, ordering it by timestamp first.
+    pub fn push_batch(&mut self, transfers: &[Transfer]) -> Result<()> {
+        let mut ordered: Vec<&Transfer> = transfers.iter().collect();
+        ordered.sort_by_key(|t| t.ts);
+
+        for transfer in ordered {
+            self.push(transfer)?;
+        }
+        Ok(())
+    }
+
+    /// Current statistics for every seen address, ordered by address.
+    pub fn snapshot(&self) -> Result<Vec<UserStats>> {
+        let watermark = self.watermark.unwrap_or_default();
+
+        let mut stats = self
+            .accounts
+            .iter()
+            .map(|(&addr, account)| account.to_stats(addr, watermark, &self.config.windows))
+            .collect::<Result<Vec<UserStats>>>()
+            .context("Failed to calculate user statistics")?;
+
+        stats.sort_by_key(|s| s.address);
+        Ok(stats)
+    }
+}
@@ -1,3 +1,12 @@
+pub mod backend;
+pub mod config;
+pub mod engine;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[allow(clippy::module_inception)]
 pub mod pipeline;
 
-pub use pipeline::calculate_user_stats;
+pub use backend::{InProcessStatsBackend, StatsBackend};
+pub use config::{BalanceWindow, PipelineConfig};
+pub use engine::{window_max, LatePolicy, StatsEngine};
+pub use pipeline::{calculate_user_stats, calculate_user_stats_with};
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+
+use crate::model::{Address, Transfer, UserStats};
+use crate::pipeline::config::PipelineConfig;
+use crate::pipeline::engine::AccountState;
+
+enum Side {
+    Send,
+    Receive,
+}
+
+/// Batch equivalent of [`StatsEngine`](crate::pipeline::StatsEngine) that
+/// replays each address on a worker thread.
+///
+/// Events keep the order the engine would apply them in, so the result is
+/// identical to the sequential one.
+pub(crate) fn calculate_user_stats(
+    transfers: &[Transfer],
+    config: &PipelineConfig,
+) -> Result<Vec<UserStats>> {
+    let mut ordered: Vec<&Transfer> = transfers.iter().collect();
+    ordered.sort_by_key(|t| t.ts);
+    let watermark = ordered.last().map(|t| t.ts).unwrap_or_default();
+
+    let mut events: HashMap<Address, Vec<(Side, &Transfer)>> = HashMap::new();
+    for t in ordered {
+        events.entry(t.from).or_default().push((Side::Send, t));
+        events.entry(t.to).or_default().push((Side::Receive, t));
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(config.threads)
+        .build()
+        .context("Failed to build stats thread pool")?;
+
+    let mut stats = pool
+        .install(|| {
+            events
+                .into_par_iter()
+                .map(|(addr, events)| {
+                    let mut account = AccountState::new(&config.windows);
+                    for (side, t) in events {
+                        match side {
+                            Side::Send => account.send(t.ts, t.amount, t.usd_price)?,
+                            Side::Receive => account.receive(t.ts, t.amount, t.usd_price)?,
+                        }
+                    }
+                    account.to_stats(addr, watermark, &config.windows)
+                })
+                .collect::<Result<Vec<UserStats>>>()
+        })
+        .context("Failed to calculate user statistics")?;
+
+    stats.par_sort_by_key(|s| s.address);
+    Ok(stats)
+}
@@ -1,81 +1,93 @@
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use clickhouse::Client;
 
 use crate::model::{Transfer, UserStats};
 use crate::storage::commands::{ClickHouseSaveStatsCommand, ClickHouseSaveTransfersCommand};
+use crate::storage::dedup::SaveMode;
 use crate::storage::errors::StorageError;
+use crate::storage::migrations::{has_live_stats, Migrator};
+use crate::storage::insert::{ChunkedInsert, InsertSummary};
 
 use crate::storage::commands::save_stats::SaveStatsCommand;
 use crate::storage::commands::save_transfers::SaveTransfersCommand;
 use crate::storage::queries::get_stats::GetStatsQuery;
-use crate::storage::queries::ClickHouseGetStatsQuery;
+use crate::storage::queries::get_transfers::GetTransfersQuery;
+use crate::storage::queries::{ClickHouseGetStatsQuery, ClickHouseGetTransfersQuery};
 
 use crate::storage::storage_trait::Storage;
 
+/// [`Storage`] backed by a ClickHouse server.
 pub struct ClickHouseStorage {
+    client: Client,
     save_transfers_cmd: ClickHouseSaveTransfersCommand,
+    get_transfers_query: ClickHouseGetTransfersQuery,
     save_stats_cmd: ClickHouseSaveStatsCommand,
     get_stats_query: ClickHouseGetStatsQuery,
 }
 
 impl ClickHouseStorage {
+    /// Checks connectivity and that the schema is at the latest
+    /// [migration](crate::storage::migrations).
     pub async fn new(client: Client) -> Result<Self, StorageError> {
+        Self::with_options(client, ChunkedInsert::default(), SaveMode::default()).await
+    }
+
+    /// Same as [`new`](Self::new), writing through `insert` in `mode`.
+    pub async fn with_options(
+        client: Client,
+        insert: ChunkedInsert,
+        mode: SaveMode,
+    ) -> Result<Self, StorageError> {
         client
             .query("SELECT 1")
             .execute()
             .await
             .map_err(StorageError::ClickHouse)?;
 
-        client
-            .query(
-                r#"
-            CREATE TABLE IF NOT EXISTS transfers (
-                ts UInt64,
-                `from` String,
-                `to` String,
-                amount Float64,
-                usd_price Float64
-            ) ENGINE = MergeTree() ORDER BY (ts, `from`, `to`)
-        "#,
-            )
-            .execute()
-            .await
-            .map_err(StorageError::ClickHouse)?;
-
-        client
-            .query(
-                r#"
-            CREATE TABLE IF NOT EXISTS user_stats (
-                address String,
-                total_volume Float64,
-                avg_buy_price Float64,
-                avg_sell_price Float64,
-                max_balance Float64,
-                max_balance_1h Float64,
-                max_balance_24h Float64,
-                max_balance_7d Float64
-            ) ENGINE = ReplacingMergeTree() ORDER BY address
-        "#,
-            )
-            .execute()
-            .await
-            .map_err(StorageError::ClickHouse)?;
+        let applied = Migrator::new(client.clone()).check().await?;
+        let mut save_transfers_cmd =
+            ClickHouseSaveTransfersCommand::new(client.clone(), insert.clone(), mode);
+        if has_live_stats(&applied) {
+            save_transfers_cmd = save_transfers_cmd.with_live_stats();
+        }
 
         Ok(Self {
-            save_transfers_cmd: ClickHouseSaveTransfersCommand::new(client.clone()),
-            save_stats_cmd: ClickHouseSaveStatsCommand::new(client.clone()),
-            get_stats_query: ClickHouseGetStatsQuery::new(client.clone()),
+            client: client.clone(),
+            save_transfers_cmd,
+            get_transfers_query: ClickHouseGetTransfersQuery::new(client.clone()),
+            save_stats_cmd: ClickHouseSaveStatsCommand::new(client.clone(), insert, mode),
+            get_stats_query: ClickHouseGetStatsQuery::new(client),
         })
     }
+
+    /// Reads stats from the [live aggregates](crate::storage::live); their
+    /// migration must be applied.
+    pub async fn with_live_stats(mut self) -> Result<Self, StorageError> {
+        Migrator::new(self.client.clone()).with_live_stats().check().await?;
+        self.get_stats_query = ClickHouseGetStatsQuery::live(self.client.clone());
+        Ok(self)
+    }
 }
 
 #[async_trait]
 impl Storage for ClickHouseStorage {
-    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<(), StorageError> {
+    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<InsertSummary, StorageError> {
         self.save_transfers_cmd.save_transfers(transfers).await
     }
 
-    async fn save_stats(&self, stats: &[UserStats]) -> Result<(), StorageError> {
+    async fn save_transfers_stream(
+        &self,
+        chunks: BoxStream<'_, Vec<Transfer>>,
+    ) -> Result<InsertSummary, StorageError> {
+        self.save_transfers_cmd.save_transfers_stream(chunks).await
+    }
+
+    async fn get_transfers(&self) -> Result<Vec<Transfer>, StorageError> {
+        self.get_transfers_query.get_transfers().await
+    }
+
+    async fn save_stats(&self, stats: &[UserStats]) -> Result<InsertSummary, StorageError> {
         self.save_stats_cmd.save_stats(stats).await
     }
 
@@ -1,5 +1,7 @@
 pub mod save_stats;
 pub mod save_transfers;
 
-pub use save_stats::ClickHouseSaveStatsCommand;
-pub use save_transfers::ClickHouseSaveTransfersCommand;
+pub use save_stats::{ClickHouseSaveStatsCommand, InMemorySaveStatsCommand, SaveStatsCommand};
+pub use save_transfers::{
+    ClickHouseSaveTransfersCommand, InMemorySaveTransfersCommand, SaveTransfersCommand,
+};
@@ -1,44 +1,100 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use clickhouse::Client;
+use tokio::sync::RwLock;
 
-use crate::model::UserStats;
+use crate::model::{Address, UserStats};
+use crate::storage::dedup::{save_version, SaveMode};
 use crate::storage::errors::StorageError;
+use crate::storage::insert::{write_rows, ChunkedInsert, InsertSummary};
+use crate::storage::memory::InMemoryState;
+use crate::storage::rows::UserStatsRow;
 
+/// Writes a batch of user statistics according to a [`SaveMode`].
 #[async_trait]
-pub trait SaveStatsCommand {
-    async fn save_stats(&self, stats: &[UserStats]) -> Result<(), StorageError>;
+pub trait SaveStatsCommand: Send + Sync {
+    async fn save_stats(&self, stats: &[UserStats]) -> Result<InsertSummary, StorageError>;
 }
 
+/// Inserts into the `user_stats` table through [`ChunkedInsert`], truncating
+/// it first in [`SaveMode::Replace`].
+///
+/// Rows carry the save time as `version`, so the `ReplacingMergeTree` keeps
+/// the latest stats of every address.
 pub struct ClickHouseSaveStatsCommand {
     client: Client,
+    insert: ChunkedInsert,
+    mode: SaveMode,
 }
 
 impl ClickHouseSaveStatsCommand {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(client: Client, insert: ChunkedInsert, mode: SaveMode) -> Self {
+        Self {
+            client,
+            insert,
+            mode,
+        }
     }
 }
 
 #[async_trait]
 impl SaveStatsCommand for ClickHouseSaveStatsCommand {
-    async fn save_stats(&self, stats: &[UserStats]) -> Result<(), StorageError> {
+    async fn save_stats(&self, stats: &[UserStats]) -> Result<InsertSummary, StorageError> {
+        let mut summary = InsertSummary::default();
         if stats.is_empty() {
-            return Ok(());
+            return Ok(summary);
+        }
+
+        if self.mode == SaveMode::Replace {
+            self.client
+                .query("TRUNCATE TABLE user_stats")
+                .execute()
+                .await
+                .map_err(StorageError::ClickHouse)?;
         }
 
-        self.client
-            .query("TRUNCATE TABLE user_stats")
-            .execute()
-            .await
-            .map_err(StorageError::ClickHouse)?;
+        let version = save_version();
+        let rows: Vec<UserStatsRow> = stats.iter().map(|s| UserStatsRow::new(s, version)).collect();
+        self.insert
+            .run("user_stats", &rows, &mut summary, |chunk| {
+                write_rows(&self.client, "user_stats", chunk)
+            })
+            .await?;
+        Ok(summary)
+    }
+}
+
+/// Updates the stats list held in [`InMemoryState`], replacing the stats of
+/// addresses saved again.
+#[derive(Clone)]
+pub struct InMemorySaveStatsCommand {
+    state: Arc<RwLock<InMemoryState>>,
+    mode: SaveMode,
+}
+
+impl InMemorySaveStatsCommand {
+    pub fn new(state: Arc<RwLock<InMemoryState>>, mode: SaveMode) -> Self {
+        Self { state, mode }
+    }
+}
 
-        let mut insert = self.client.insert("user_stats")?;
+#[async_trait]
+impl SaveStatsCommand for InMemorySaveStatsCommand {
+    async fn save_stats(&self, stats: &[UserStats]) -> Result<InsertSummary, StorageError> {
+        if stats.is_empty() {
+            return Ok(InsertSummary::default());
+        }
 
-        for s in stats {
-            insert.write(s).await.map_err(StorageError::ClickHouse)?;
+        let mut state = self.state.write().await;
+        if self.mode == SaveMode::Replace {
+            state.stats.clear();
         }
 
-        insert.end().await.map_err(StorageError::ClickHouse)?;
-        Ok(())
+        let saved: HashSet<Address> = stats.iter().map(|s| s.address).collect();
+        state.stats.retain(|s| !saved.contains(&s.address));
+        state.stats.extend_from_slice(stats);
+        Ok(InsertSummary::single(stats.len()))
     }
 }
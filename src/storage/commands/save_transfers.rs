@@ -1,44 +1,225 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use clickhouse::Client;
+use futures::stream::{BoxStream, StreamExt};
+use tokio::sync::RwLock;
 
 use crate::model::Transfer;
+use crate::storage::dedup::{SaveMode, TransferKeys};
 use crate::storage::errors::StorageError;
+use crate::storage::insert::{write_rows, ChunkedInsert, InsertSummary};
+use crate::storage::live::LiveStats;
+use crate::storage::memory::InMemoryState;
+use crate::storage::rows::TransferRow;
+
+/// Transfer keys per lookup of the stored ones, small enough to keep the
+/// query under ClickHouse's default `max_query_size`.
+const KEY_LOOKUP_BATCH: usize = 1_000;
 
+/// Writes a batch of transfers according to a [`SaveMode`].
 #[async_trait]
-pub trait SaveTransfersCommand {
-    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<(), StorageError>;
+pub trait SaveTransfersCommand: Send + Sync {
+    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<InsertSummary, StorageError>;
+
+    /// Same as [`save_transfers`](Self::save_transfers) for a stream of
+    /// chunks, keyed together as one batch. An empty stream is a no-op.
+    async fn save_transfers_stream(
+        &self,
+        chunks: BoxStream<'_, Vec<Transfer>>,
+    ) -> Result<InsertSummary, StorageError>;
 }
 
+/// Inserts into the `transfers` table through [`ChunkedInsert`], truncating
+/// it first in [`SaveMode::Replace`].
+///
+/// The table is a `ReplacingMergeTree` ordered by the transfer key, so rows
+/// saved twice collapse on merge and are skipped by `SELECT ... FINAL`.
 pub struct ClickHouseSaveTransfersCommand {
     client: Client,
+    insert: ChunkedInsert,
+    mode: SaveMode,
+    live: bool,
 }
 
 impl ClickHouseSaveTransfersCommand {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(client: Client, insert: ChunkedInsert, mode: SaveMode) -> Self {
+        Self {
+            client,
+            insert,
+            mode,
+            live: false,
+        }
     }
-}
 
-#[async_trait]
-impl SaveTransfersCommand for ClickHouseSaveTransfersCommand {
-    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<(), StorageError> {
-        if transfers.is_empty() {
+    /// Also empties the [live aggregates](crate::storage::live) when
+    /// replacing transfers, and skips transfers already stored, which their
+    /// view would count again. Concurrent saves of the same transfers can
+    /// still both be counted.
+    pub fn with_live_stats(mut self) -> Self {
+        self.live = true;
+        self
+    }
+
+    async fn prepare(&self) -> Result<(), StorageError> {
+        if self.mode == SaveMode::Append {
             return Ok(());
         }
-
         self.client
             .query("TRUNCATE TABLE transfers")
             .execute()
             .await
             .map_err(StorageError::ClickHouse)?;
+        if self.live {
+            LiveStats::new(self.client.clone()).clear().await?;
+        }
+        Ok(())
+    }
 
-        let mut insert = self.client.insert("transfers")?;
+    /// Keys of `rows` that are already stored, looked up
+    /// [`KEY_LOOKUP_BATCH`] rows at a time.
+    async fn stored_keys(&self, rows: &[TransferRow]) -> Result<HashSet<u128>, StorageError> {
+        let mut stored = HashSet::new();
+        for batch in rows.chunks(KEY_LOOKUP_BATCH) {
+            let min = batch.iter().map(|r| r.ts).min().unwrap_or_default();
+            let max = batch.iter().map(|r| r.ts).max().unwrap_or_default();
+            // Keys go as strings: integer literals beyond UInt64 may parse
+            // as Float64. The time range lets the sorting key skip parts.
+            let keys: Vec<String> = batch.iter().map(|r| r.key.to_string()).collect();
+            let found = self
+                .client
+                .query(
+                    "SELECT key FROM transfers WHERE ts BETWEEN ? AND ? \
+                     AND key IN (SELECT toUInt128(arrayJoin(?)))",
+                )
+                .bind(min)
+                .bind(max)
+                .bind(keys)
+                .fetch_all::<u128>()
+                .await?;
+            stored.extend(found);
+        }
+        Ok(stored)
+    }
 
-        for t in transfers {
-            insert.write(t).await.map_err(StorageError::ClickHouse)?;
+    async fn write(
+        &self,
+        transfers: &[Transfer],
+        keys: &mut TransferKeys,
+        summary: &mut InsertSummary,
+    ) -> Result<(), StorageError> {
+        let mut rows: Vec<TransferRow> = keys
+            .next_chunk(transfers)
+            .into_iter()
+            .zip(transfers)
+            .map(|(key, t)| TransferRow::new(key, t))
+            .collect();
+        if self.live {
+            let stored = self.stored_keys(&rows).await?;
+            rows.retain(|row| !stored.contains(&row.key));
         }
 
-        insert.end().await.map_err(StorageError::ClickHouse)?;
-        Ok(())
+        self.insert
+            .run("transfers", &rows, summary, |chunk| {
+                write_rows(&self.client, "transfers", chunk)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl SaveTransfersCommand for ClickHouseSaveTransfersCommand {
+    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<InsertSummary, StorageError> {
+        let mut summary = InsertSummary::default();
+        if transfers.is_empty() {
+            return Ok(summary);
+        }
+
+        self.prepare().await?;
+        self.write(transfers, &mut TransferKeys::default(), &mut summary).await?;
+        Ok(summary)
+    }
+
+    async fn save_transfers_stream(
+        &self,
+        chunks: BoxStream<'_, Vec<Transfer>>,
+    ) -> Result<InsertSummary, StorageError> {
+        let mut summary = InsertSummary::default();
+        let mut chunks = chunks.filter(|chunk| futures::future::ready(!chunk.is_empty()));
+        let Some(first) = chunks.next().await else {
+            return Ok(summary);
+        };
+
+        self.prepare().await?;
+
+        let mut keys = TransferKeys::default();
+        let mut chunk = Some(first);
+        while let Some(transfers) = chunk {
+            self.write(&transfers, &mut keys, &mut summary).await?;
+            chunk = chunks.next().await;
+        }
+        Ok(summary)
+    }
+}
+
+/// Updates the transfer list held in [`InMemoryState`], skipping transfers
+/// whose key is already stored.
+#[derive(Clone)]
+pub struct InMemorySaveTransfersCommand {
+    state: Arc<RwLock<InMemoryState>>,
+    mode: SaveMode,
+}
+
+impl InMemorySaveTransfersCommand {
+    pub fn new(state: Arc<RwLock<InMemoryState>>, mode: SaveMode) -> Self {
+        Self { state, mode }
+    }
+
+    async fn apply(&self, transfers: &[Transfer], mode: SaveMode, keys: &mut TransferKeys) {
+        let mut state = self.state.write().await;
+        if mode == SaveMode::Replace {
+            state.transfers.clear();
+            state.transfer_keys.clear();
+        }
+
+        for (key, transfer) in keys.next_chunk(transfers).into_iter().zip(transfers) {
+            if state.transfer_keys.insert(key) {
+                state.transfers.push((key, transfer.clone()));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SaveTransfersCommand for InMemorySaveTransfersCommand {
+    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<InsertSummary, StorageError> {
+        if transfers.is_empty() {
+            return Ok(InsertSummary::default());
+        }
+
+        self.apply(transfers, self.mode, &mut TransferKeys::default()).await;
+        Ok(InsertSummary::single(transfers.len()))
+    }
+
+    async fn save_transfers_stream(
+        &self,
+        mut chunks: BoxStream<'_, Vec<Transfer>>,
+    ) -> Result<InsertSummary, StorageError> {
+        let mut summary = InsertSummary::default();
+        let mut mode = self.mode;
+        let mut keys = TransferKeys::default();
+
+        while let Some(transfers) = chunks.next().await {
+            if transfers.is_empty() {
+                continue;
+            }
+            self.apply(&transfers, mode, &mut keys).await;
+            mode = SaveMode::Append;
+
+            summary.rows += transfers.len() as u64;
+            summary.chunks += 1;
+        }
+        Ok(summary)
     }
 }
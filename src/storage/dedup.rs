@@ -0,0 +1,106 @@
+//! Save modes and the identity keys that make appends idempotent.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+
+use crate::model::Transfer;
+use crate::storage::errors::StorageError;
+
+/// What a save does with the rows already stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveMode {
+    /// Keep stored rows. Transfers are deduplicated by [`transfer_keys`],
+    /// statistics by address with the latest save winning.
+    Append,
+    /// Drop everything stored before writing the batch.
+    #[default]
+    Replace,
+}
+
+impl FromStr for SaveMode {
+    type Err = StorageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "append" => Ok(Self::Append),
+            "replace" => Ok(Self::Replace),
+            _ => Err(StorageError::InvalidConfig(format!(
+                "Unknown save mode '{}', expected append or replace",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for SaveMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Append => "append",
+            Self::Replace => "replace",
+        })
+    }
+}
+
+/// Identity of every transfer in `transfers`.
+///
+/// Transfers carry no transaction hash, so the key hashes all fields and adds
+/// the number of identical transfers before it in the batch. Loading the same
+/// or an overlapping batch again yields the same keys, while repeated
+/// identical transfers within a batch stay distinct.
+pub fn transfer_keys(transfers: &[Transfer]) -> Vec<u128> {
+    TransferKeys::default().next_chunk(transfers)
+}
+
+/// [`transfer_keys`] for a batch that arrives in chunks.
+///
+/// Occurrences are counted across all chunks passed to one keyer, so
+/// identical transfers split between chunks get the keys they would get in
+/// a single batch. Holds one entry per distinct transfer seen.
+#[derive(Debug, Default)]
+pub struct TransferKeys {
+    seen: HashMap<u128, u128>,
+}
+
+impl TransferKeys {
+    /// Keys of the next chunk of the batch.
+    pub fn next_chunk(&mut self, transfers: &[Transfer]) -> Vec<u128> {
+        transfers
+            .iter()
+            .map(|transfer| {
+                let content = content_hash(transfer);
+                let occurrence = self.seen.entry(content).or_default();
+                let key = content.wrapping_add(*occurrence);
+                *occurrence += 1;
+                key
+            })
+            .collect()
+    }
+}
+
+fn content_hash(transfer: &Transfer) -> u128 {
+    let digest = Keccak256::new()
+        .chain_update(transfer.ts.to_le_bytes())
+        .chain_update(transfer.from.as_bytes())
+        .chain_update(transfer.to.as_bytes())
+        .chain_update(transfer.amount.raw().to_le_bytes())
+        .chain_update(transfer.usd_price.to_bits().to_le_bytes())
+        .finalize();
+
+    let mut key = [0; 16];
+    key.copy_from_slice(&digest[..16]);
+    u128::from_le_bytes(key)
+}
+
+/// Row version for a save made now: milliseconds since the epoch.
+pub fn save_version() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -1,7 +1,27 @@
 use thiserror::Error;
 
+/// Errors returned by every [`Storage`](crate::storage::Storage) backend.
 #[derive(Error, Debug)]
 pub enum StorageError {
     #[error("ClickHouse error: {0}")]
     ClickHouse(#[from] clickhouse::error::Error),
+    /// A chunk failed for good; earlier chunks of the save stay written.
+    #[error("Insert into {table} failed after {written} rows: {source}")]
+    Insert {
+        table: &'static str,
+        written: u64,
+        source: clickhouse::error::Error,
+    },
+    #[error("Invalid storage config: {0}")]
+    InvalidConfig(String),
+    #[error("Schema version {found} is unknown to this build, which knows up to {latest}")]
+    UnknownSchemaVersion { found: u32, latest: u32 },
+    #[error("Schema is at version {current}, expected {latest}; run the migrations first")]
+    SchemaOutdated { current: u32, latest: u32 },
+    #[error("No migration with version {0}")]
+    UnknownMigration(u32),
+    #[error("Migration {0} is part of the live statistics, which are not enabled")]
+    LiveStatsDisabled(u32),
+    #[error("Cannot migrate a legacy {table} row: {reason}")]
+    LegacyRow { table: &'static str, reason: String },
 }
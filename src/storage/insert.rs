@@ -0,0 +1,202 @@
+//! Chunked inserts with retry, shared by the ClickHouse commands.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clickhouse::error::Error;
+use clickhouse::{Client, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::errors::StorageError;
+
+/// Server error codes worth retrying: timeouts, overload, too many parts,
+/// read-only replicas and lost connections.
+const TRANSIENT_CODES: [u32; 10] = [159, 202, 209, 210, 241, 242, 252, 319, 425, 999];
+
+/// How rows are split into INSERT requests and how failed requests are retried.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InsertConfig {
+    /// Rows per INSERT request.
+    pub chunk_size: usize,
+    /// Retries of a chunk after a transient error; `0` fails on the first one.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every following one.
+    pub initial_backoff_ms: u64,
+    /// Upper bound of the delay between retries.
+    pub max_backoff_ms: u64,
+}
+
+impl Default for InsertConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: 100_000,
+            max_retries: 5,
+            initial_backoff_ms: 200,
+            max_backoff_ms: 10_000,
+        }
+    }
+}
+
+impl InsertConfig {
+    /// Delay before retry number `retry` (0-based).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64.checked_shl(retry).unwrap_or(u64::MAX);
+        let millis = self.initial_backoff_ms.saturating_mul(factor);
+        Duration::from_millis(millis.min(self.max_backoff_ms))
+    }
+}
+
+/// Reported after every chunk that reached the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsertProgress {
+    pub table: &'static str,
+    /// 1-based number of the chunk within the save.
+    pub chunk: usize,
+    /// Rows in this chunk.
+    pub rows: u64,
+    /// Rows written by the save so far, this chunk included.
+    pub written: u64,
+    /// Retries this chunk needed.
+    pub retries: u32,
+}
+
+/// Outcome of a save.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InsertSummary {
+    pub rows: u64,
+    pub chunks: usize,
+    /// Retries over all chunks.
+    pub retries: u32,
+}
+
+impl InsertSummary {
+    /// Summary of `rows` written at once, as the in-memory backend does.
+    pub fn single(rows: usize) -> Self {
+        Self {
+            rows: rows as u64,
+            chunks: 1,
+            retries: 0,
+        }
+    }
+}
+
+pub type ProgressCallback = Arc<dyn Fn(&InsertProgress) + Send + Sync>;
+
+/// Splits rows into chunks of [`InsertConfig::chunk_size`], writes each with
+/// its own INSERT and retries chunks that fail with a transient error.
+///
+/// Chunks written before a failure stay in the table. A chunk is re-sent as a
+/// whole, so one the server committed before the connection dropped is
+/// written twice.
+#[derive(Clone, Default)]
+pub struct ChunkedInsert {
+    config: InsertConfig,
+    progress: Option<ProgressCallback>,
+}
+
+impl ChunkedInsert {
+    pub fn new(config: InsertConfig) -> Result<Self, StorageError> {
+        if config.chunk_size == 0 {
+            return Err(StorageError::InvalidConfig("chunk_size must be positive".to_string()));
+        }
+        Ok(Self {
+            config,
+            progress: None,
+        })
+    }
+
+    /// Calls `callback` after every written chunk.
+    pub fn on_progress(
+        mut self,
+        callback: impl Fn(&InsertProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn config(&self) -> &InsertConfig {
+        &self.config
+    }
+
+    /// Writes `rows` chunk by chunk with `write`, adding to `summary` so that
+    /// several calls can make up one save.
+    pub async fn run<'a, T, F, Fut>(
+        &self,
+        table: &'static str,
+        rows: &'a [T],
+        summary: &mut InsertSummary,
+        mut write: F,
+    ) -> Result<(), StorageError>
+    where
+        F: FnMut(&'a [T]) -> Fut,
+        Fut: Future<Output = Result<(), Error>>,
+    {
+        for chunk in rows.chunks(self.config.chunk_size) {
+            let mut retries = 0;
+            loop {
+                match write(chunk).await {
+                    Ok(()) => break,
+                    Err(error) if retries < self.config.max_retries && is_transient(&error) => {
+                        tokio::time::sleep(self.config.backoff(retries)).await;
+                        retries += 1;
+                    }
+                    Err(source) => {
+                        return Err(StorageError::Insert {
+                            table,
+                            written: summary.rows,
+                            source,
+                        })
+                    }
+                }
+            }
+
+            summary.rows += chunk.len() as u64;
+            summary.chunks += 1;
+            summary.retries += retries;
+            if let Some(progress) = &self.progress {
+                progress(&InsertProgress {
+                    table,
+                    chunk: summary.chunks,
+                    rows: chunk.len() as u64,
+                    written: summary.rows,
+                    retries,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends `rows` as one INSERT into `table`.
+pub async fn write_rows<T>(client: &Client, table: &str, rows: &[T]) -> Result<(), Error>
+where
+    T: Row + Serialize,
+{
+    let mut insert = client.insert(table)?;
+    for row in rows {
+        insert.write(row).await?;
+    }
+    insert.end().await
+}
+
+/// Whether `error` may go away when the request is repeated: network
+/// failures, timeouts, gateway errors and the server codes in
+/// `TRANSIENT_CODES`.
+pub fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Network(_) | Error::TimedOut => true,
+        Error::BadResponse(reason) => {
+            if ["502", "503", "504"].iter().any(|status| reason.starts_with(status)) {
+                return true;
+            }
+            let code = reason
+                .strip_prefix("Code: ")
+                .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .and_then(|digits| digits.parse().ok());
+            code.is_some_and(|code| TRANSIENT_CODES.contains(&code))
+        }
+        _ => false,
+    }
+}
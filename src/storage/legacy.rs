@@ -0,0 +1,189 @@
+//! Tables created before [migrations](crate::storage::migrations) existed.
+//!
+//! Earlier builds created `transfers` and `user_stats` on startup, with
+//! `String` addresses and `Float64` amounts. [`Migrator::up`] sets such tables
+//! aside as `<table>_legacy`, creates the migrated ones and copies the rows
+//! over in batches, keying transfers with [`TransferKeys`] as if the whole
+//! table was one batch.
+//!
+//! [`Migrator::up`]: crate::storage::Migrator::up
+
+use clickhouse::{Client, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Address, AddressError, Amount, Transfer};
+use crate::storage::dedup::TransferKeys;
+use crate::storage::errors::StorageError;
+use crate::storage::insert::{write_rows, ChunkedInsert, InsertSummary};
+use crate::storage::rows::{TransferRow, UserStatsRow};
+
+/// Rows read from a legacy table before they are written.
+const COPY_BATCH: usize = 100_000;
+
+/// Tables of the pre-migration layout with a column that was a `String`.
+const TABLES: [(&str, &str); 2] = [("transfers", "from"), ("user_stats", "address")];
+
+#[derive(Debug, Deserialize, Row)]
+struct LegacyTransferRow {
+    ts: u64,
+    from: String,
+    to: String,
+    amount: f64,
+    usd_price: f64,
+}
+
+#[derive(Debug, Deserialize, Row)]
+struct LegacyStatsRow {
+    address: String,
+    total_volume: f64,
+    avg_buy_price: f64,
+    avg_sell_price: f64,
+    max_balance: f64,
+    max_balance_1h: f64,
+    max_balance_24h: f64,
+    max_balance_7d: f64,
+}
+
+/// Renames tables in the pre-migration layout to `<table>_legacy`.
+pub(crate) async fn set_aside(client: &Client) -> Result<(), StorageError> {
+    for (table, column) in TABLES {
+        let column_type = client
+            .query(
+                "SELECT type FROM system.columns \
+                 WHERE database = currentDatabase() AND table = ? AND name = ?",
+            )
+            .bind(table)
+            .bind(column)
+            .fetch_optional::<String>()
+            .await?;
+        if column_type.as_deref() == Some("String") {
+            client
+                .query(&format!("RENAME TABLE {} TO {}_legacy", table, table))
+                .execute()
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies every set-aside table whose migrated table exists and drops it.
+/// Copying again after a failure rewrites the same rows.
+pub(crate) async fn copy(client: &Client) -> Result<(), StorageError> {
+    if exists(client, "transfers_legacy").await? && exists(client, "transfers").await? {
+        copy_transfers(client).await?;
+        client.query("DROP TABLE transfers_legacy").execute().await?;
+    }
+    if exists(client, "user_stats_legacy").await? && exists(client, "user_stats").await? {
+        copy_stats(client).await?;
+        client.query("DROP TABLE user_stats_legacy").execute().await?;
+    }
+    Ok(())
+}
+
+async fn exists(client: &Client, table: &str) -> Result<bool, StorageError> {
+    let exists = client
+        .query(&format!("EXISTS TABLE {}", table))
+        .fetch_one::<u8>()
+        .await?;
+    Ok(exists == 1)
+}
+
+async fn copy_transfers(client: &Client) -> Result<(), StorageError> {
+    let mut cursor = client
+        .query(
+            "SELECT ts, `from`, `to`, amount, usd_price FROM transfers_legacy \
+             ORDER BY ts, `from`, `to`",
+        )
+        .fetch::<LegacyTransferRow>()?;
+    let mut keys = TransferKeys::default();
+    let mut batch = Vec::with_capacity(COPY_BATCH);
+
+    while let Some(row) = cursor.next().await? {
+        batch.push(Transfer {
+            ts: row.ts,
+            from: address("transfers", &row.from)?,
+            to: address("transfers", &row.to)?,
+            amount: amount("transfers", row.amount)?,
+            usd_price: row.usd_price,
+        });
+        if batch.len() == COPY_BATCH {
+            write_transfers(client, &mut keys, &batch).await?;
+            batch.clear();
+        }
+    }
+    write_transfers(client, &mut keys, &batch).await
+}
+
+async fn write_transfers(
+    client: &Client,
+    keys: &mut TransferKeys,
+    transfers: &[Transfer],
+) -> Result<(), StorageError> {
+    let rows: Vec<TransferRow> = keys
+        .next_chunk(transfers)
+        .into_iter()
+        .zip(transfers)
+        .map(|(key, t)| TransferRow::new(key, t))
+        .collect();
+    write(client, "transfers", &rows).await
+}
+
+async fn copy_stats(client: &Client) -> Result<(), StorageError> {
+    let mut cursor = client
+        .query(
+            "SELECT address, total_volume, avg_buy_price, avg_sell_price, max_balance, \
+             max_balance_1h, max_balance_24h, max_balance_7d FROM user_stats_legacy FINAL",
+        )
+        .fetch::<LegacyStatsRow>()?;
+    let mut batch = Vec::with_capacity(COPY_BATCH);
+
+    while let Some(row) = cursor.next().await? {
+        let windows = [
+            ("1h", row.max_balance_1h),
+            ("24h", row.max_balance_24h),
+            ("7d", row.max_balance_7d),
+        ];
+        batch.push(UserStatsRow {
+            address: address("user_stats", &row.address)?,
+            total_volume: amount("user_stats", row.total_volume)?,
+            avg_buy_price: row.avg_buy_price,
+            avg_sell_price: row.avg_sell_price,
+            max_balance: amount("user_stats", row.max_balance)?,
+            max_balance_windows: windows
+                .into_iter()
+                .map(|(label, value)| Ok((label.to_string(), amount("user_stats", value)?)))
+                .collect::<Result<_, StorageError>>()?,
+            version: 0,
+        });
+        if batch.len() == COPY_BATCH {
+            write(client, "user_stats", &batch).await?;
+            batch.clear();
+        }
+    }
+    write(client, "user_stats", &batch).await
+}
+
+async fn write<T>(client: &Client, table: &'static str, rows: &[T]) -> Result<(), StorageError>
+where
+    T: Row + Serialize,
+{
+    ChunkedInsert::default()
+        .run(table, rows, &mut InsertSummary::default(), |chunk| {
+            write_rows(client, table, chunk)
+        })
+        .await
+}
+
+fn address(table: &'static str, value: &str) -> Result<Address, StorageError> {
+    value.parse().map_err(|error: AddressError| StorageError::LegacyRow {
+        table,
+        reason: error.to_string(),
+    })
+}
+
+fn amount(table: &'static str, value: f64) -> Result<Amount, StorageError> {
+    Amount::from_f64(value).ok_or_else(|| StorageError::LegacyRow {
+        table,
+        reason: format!("amount {} is out of the Decimal128(18) range", value),
+    })
+}
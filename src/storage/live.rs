@@ -0,0 +1,57 @@
+//! Continuously updated statistics in ClickHouse.
+//!
+//! A materialized view on `transfers` folds every inserted block into
+//! per-address partial aggregates in `user_stats_agg`, an
+//! `AggregatingMergeTree`. Volume and average prices are then available
+//! without running the pipeline; balances still come from `user_stats`.
+//! The table and the view are created, and the aggregates backfilled, by
+//! the live statistics [migration](crate::storage::migrations), which holds
+//! the only definition of the aggregation.
+
+use clickhouse::Client;
+
+use crate::storage::errors::StorageError;
+
+/// Finalized [`UserStatsRow`](crate::storage::rows::UserStatsRow)s: volume
+/// and prices merged from `user_stats_agg`, balances and version from the
+/// latest `user_stats` row of the address (zero and empty without one).
+pub const SELECT_LIVE_STATS: &str = r#"
+    SELECT
+        agg.address AS address,
+        agg.total_volume AS total_volume,
+        if(agg.buy_amount > 0, agg.buy_value / toFloat64(agg.buy_amount), 0) AS avg_buy_price,
+        if(agg.sell_amount > 0, agg.sell_value / toFloat64(agg.sell_amount), 0) AS avg_sell_price,
+        saved.max_balance AS max_balance,
+        saved.max_balance_windows AS max_balance_windows,
+        saved.version AS version
+    FROM (
+        SELECT
+            address,
+            sumMerge(total_volume) AS total_volume,
+            sumMerge(buy_amount) AS buy_amount,
+            sumMerge(buy_value) AS buy_value,
+            sumMerge(sell_amount) AS sell_amount,
+            sumMerge(sell_value) AS sell_value
+        FROM user_stats_agg
+        GROUP BY address
+    ) AS agg
+    LEFT JOIN (SELECT * FROM user_stats FINAL) AS saved ON saved.address = agg.address
+    ORDER BY total_volume DESC
+"#;
+
+/// Maintains the aggregates in `user_stats_agg`.
+pub struct LiveStats {
+    client: Client,
+}
+
+impl LiveStats {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Empties the aggregates, as replacing all transfers requires.
+    pub async fn clear(&self) -> Result<(), StorageError> {
+        self.client.query("TRUNCATE TABLE user_stats_agg").execute().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use tokio::sync::RwLock;
+
+use crate::model::{Transfer, UserStats};
+use crate::storage::commands::{InMemorySaveStatsCommand, InMemorySaveTransfersCommand};
+use crate::storage::dedup::SaveMode;
+use crate::storage::errors::StorageError;
+use crate::storage::insert::InsertSummary;
+
+use crate::storage::commands::save_stats::SaveStatsCommand;
+use crate::storage::commands::save_transfers::SaveTransfersCommand;
+use crate::storage::queries::get_stats::GetStatsQuery;
+use crate::storage::queries::get_transfers::GetTransfersQuery;
+use crate::storage::queries::{InMemoryGetStatsQuery, InMemoryGetTransfersQuery};
+
+use crate::storage::storage_trait::Storage;
+
+/// Data shared by the in-memory commands and queries.
+#[derive(Debug, Default)]
+pub struct InMemoryState {
+    /// Transfers with their keys, see
+    /// [`transfer_keys`](crate::storage::dedup::transfer_keys).
+    pub transfers: Vec<(u128, Transfer)>,
+    /// Keys of `transfers`.
+    pub transfer_keys: HashSet<u128>,
+    pub stats: Vec<UserStats>,
+}
+
+/// Thread-safe [`Storage`] that keeps everything in process memory.
+///
+/// Clones share the same underlying state.
+#[derive(Clone)]
+pub struct InMemoryStorage {
+    save_transfers_cmd: InMemorySaveTransfersCommand,
+    get_transfers_query: InMemoryGetTransfersQuery,
+    save_stats_cmd: InMemorySaveStatsCommand,
+    get_stats_query: InMemoryGetStatsQuery,
+}
+
+impl InMemoryStorage {
+    /// Storage saving in the default [`SaveMode`].
+    pub fn new() -> Self {
+        Self::with_mode(SaveMode::default())
+    }
+
+    pub fn with_mode(mode: SaveMode) -> Self {
+        let state = Arc::new(RwLock::new(InMemoryState::default()));
+
+        Self {
+            save_transfers_cmd: InMemorySaveTransfersCommand::new(state.clone(), mode),
+            get_transfers_query: InMemoryGetTransfersQuery::new(state.clone()),
+            save_stats_cmd: InMemorySaveStatsCommand::new(state.clone(), mode),
+            get_stats_query: InMemoryGetStatsQuery::new(state),
+        }
+    }
+}
+
+impl Default for InMemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Storage for InMemoryStorage {
+    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<InsertSummary, StorageError> {
+        self.save_transfers_cmd.save_transfers(transfers).await
+    }
+
+    async fn save_transfers_stream(
+        &self,
+        chunks: BoxStream<'_, Vec<Transfer>>,
+    ) -> Result<InsertSummary, StorageError> {
+        self.save_transfers_cmd.save_transfers_stream(chunks).await
+    }
+
+    async fn get_transfers(&self) -> Result<Vec<Transfer>, StorageError> {
+        self.get_transfers_query.get_transfers().await
+    }
+
+    async fn save_stats(&self, stats: &[UserStats]) -> Result<InsertSummary, StorageError> {
+        self.save_stats_cmd.save_stats(stats).await
+    }
+
+    async fn get_stats(&self) -> Result<Vec<UserStats>, StorageError> {
+        self.get_stats_query.get_stats().await
+    }
+}
@@ -0,0 +1,252 @@
+//! Versioned schema migrations for the ClickHouse tables.
+//!
+//! Migrations are SQL files in `migrations/`, embedded at build time. Applied
+//! versions are recorded in `schema_migrations`, which keeps the latest
+//! up/down of every version. Migrations of the [live statistics](crate::storage::live)
+//! apply only when a [`Migrator`] is built [`with_live_stats`](Migrator::with_live_stats).
+
+use clickhouse::{Client, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::dedup::save_version;
+use crate::storage::errors::StorageError;
+use crate::storage::legacy;
+
+/// One schema change with the statements that undo it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    /// Part of the optional live statistics schema.
+    pub live_stats: bool,
+}
+
+macro_rules! migration {
+    ($version:literal, $name:literal) => {
+        migration!($version, $name, false)
+    };
+    ($version:literal, $name:literal, live_stats) => {
+        migration!($version, $name, true)
+    };
+    ($version:literal, $name:literal, $live_stats:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("../../migrations/", $name, ".up.sql")),
+            down: include_str!(concat!("../../migrations/", $name, ".down.sql")),
+            live_stats: $live_stats,
+        }
+    };
+}
+
+/// Every migration, ordered by version.
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_create_transfers"),
+    migration!(2, "0002_create_user_stats"),
+    migration!(3, "0003_create_live_stats", live_stats),
+];
+
+const CREATE_SCHEMA_MIGRATIONS: &str = r#"
+    CREATE TABLE IF NOT EXISTS schema_migrations (
+        version UInt32,
+        name String,
+        applied Bool,
+        changed_at UInt64
+    ) ENGINE = ReplacingMergeTree(changed_at) ORDER BY version
+"#;
+
+/// Schema version this build reads and writes.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Latest version among [`required`] migrations.
+pub fn latest_required(live_stats: bool) -> u32 {
+    required(live_stats).last().map_or(0, |m| m.version)
+}
+
+/// Migrations a database needs, without the live statistics ones unless
+/// `live_stats` is set.
+pub fn required(live_stats: bool) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| live_stats || !m.live_stats)
+}
+
+impl Migration {
+    /// Statements of `sql`, split on `;`.
+    pub fn statements(sql: &str) -> impl Iterator<Item = &str> {
+        sql.split(';').map(str::trim).filter(|s| !s.is_empty())
+    }
+}
+
+/// Whether `applied` includes the live statistics migrations.
+pub fn has_live_stats(applied: &[u32]) -> bool {
+    MIGRATIONS.iter().filter(|m| m.live_stats).all(|m| applied.contains(&m.version))
+}
+
+/// Fails if a version in `applied` has no migration in this build.
+pub fn check_known(applied: &[u32]) -> Result<(), StorageError> {
+    match applied.iter().find(|&&v| !MIGRATIONS.iter().any(|m| m.version == v)) {
+        Some(&found) => Err(StorageError::UnknownSchemaVersion {
+            found,
+            latest: latest_version(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Migrations to apply, oldest first, to bring `applied` up to `target`.
+/// A `target` among the live statistics migrations needs `live_stats`.
+pub fn pending(
+    applied: &[u32],
+    target: u32,
+    live_stats: bool,
+) -> Result<Vec<&'static Migration>, StorageError> {
+    check_known(applied)?;
+    check_target(target)?;
+    if !live_stats && MIGRATIONS.iter().any(|m| m.version == target && m.live_stats) {
+        return Err(StorageError::LiveStatsDisabled(target));
+    }
+    Ok(required(live_stats)
+        .filter(|m| m.version <= target && !applied.contains(&m.version))
+        .collect())
+}
+
+/// Migrations to revert, newest first, to bring `applied` down to `target`.
+pub fn to_revert(applied: &[u32], target: u32) -> Result<Vec<&'static Migration>, StorageError> {
+    check_known(applied)?;
+    check_target(target)?;
+    Ok(MIGRATIONS
+        .iter()
+        .rev()
+        .filter(|m| m.version > target && applied.contains(&m.version))
+        .collect())
+}
+
+fn check_target(target: u32) -> Result<(), StorageError> {
+    if target == 0 || MIGRATIONS.iter().any(|m| m.version == target) {
+        Ok(())
+    } else {
+        Err(StorageError::UnknownMigration(target))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Row)]
+struct MigrationRow {
+    version: u32,
+    name: String,
+    applied: bool,
+    changed_at: u64,
+}
+
+/// Applies and reverts [`MIGRATIONS`] on a ClickHouse database.
+pub struct Migrator {
+    client: Client,
+    live_stats: bool,
+}
+
+impl Migrator {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            live_stats: false,
+        }
+    }
+
+    /// Also applies and requires the live statistics migrations.
+    pub fn with_live_stats(mut self) -> Self {
+        self.live_stats = true;
+        self
+    }
+
+    /// Applied versions in ascending order; creates `schema_migrations` if
+    /// it is missing.
+    pub async fn applied(&self) -> Result<Vec<u32>, StorageError> {
+        self.client.query(CREATE_SCHEMA_MIGRATIONS).execute().await?;
+
+        let applied = self
+            .client
+            .query("SELECT version FROM schema_migrations FINAL WHERE applied ORDER BY version")
+            .fetch_all::<u32>()
+            .await?;
+        Ok(applied)
+    }
+
+    /// Applied version the database is at, `0` for an empty one.
+    pub async fn current_version(&self) -> Result<u32, StorageError> {
+        Ok(self.applied().await?.last().copied().unwrap_or_default())
+    }
+
+    /// Refuses a database with versions unknown to this build or with
+    /// pending migrations. Returns the applied versions.
+    pub async fn check(&self) -> Result<Vec<u32>, StorageError> {
+        let applied = self.applied().await?;
+        check_known(&applied)?;
+
+        if required(self.live_stats).any(|m| !applied.contains(&m.version)) {
+            return Err(StorageError::SchemaOutdated {
+                current: applied.last().copied().unwrap_or_default(),
+                latest: latest_required(self.live_stats),
+            });
+        }
+        Ok(applied)
+    }
+
+    /// Applies pending migrations up to `target`, by default the latest of
+    /// the [`required`] ones.
+    /// Returns the applied versions.
+    ///
+    /// Tables created before migrations existed are [set aside](legacy) and
+    /// their rows copied into the migrated tables once those exist.
+    pub async fn up(&self, target: Option<u32>) -> Result<Vec<u32>, StorageError> {
+        legacy::set_aside(&self.client).await?;
+        let applied = self.applied().await?;
+        legacy::copy(&self.client).await?;
+        let mut done = Vec::new();
+
+        let target = target.unwrap_or_else(|| latest_required(self.live_stats));
+        for migration in pending(&applied, target, self.live_stats)? {
+            self.execute(migration.up).await?;
+            self.record(migration, true).await?;
+            legacy::copy(&self.client).await?;
+            done.push(migration.version);
+        }
+        Ok(done)
+    }
+
+    /// Reverts applied migrations newer than `target`. Returns the reverted
+    /// versions.
+    pub async fn down(&self, target: u32) -> Result<Vec<u32>, StorageError> {
+        let applied = self.applied().await?;
+        let mut done = Vec::new();
+
+        for migration in to_revert(&applied, target)? {
+            self.execute(migration.down).await?;
+            self.record(migration, false).await?;
+            done.push(migration.version);
+        }
+        Ok(done)
+    }
+
+    async fn execute(&self, sql: &str) -> Result<(), StorageError> {
+        for statement in Migration::statements(sql) {
+            self.client.query(statement).execute().await?;
+        }
+        Ok(())
+    }
+
+    async fn record(&self, migration: &Migration, applied: bool) -> Result<(), StorageError> {
+        let mut insert = self.client.insert("schema_migrations")?;
+        insert
+            .write(&MigrationRow {
+                version: migration.version,
+                name: migration.name.to_string(),
+                applied,
+                changed_at: save_version(),
+            })
+            .await?;
+        insert.end().await?;
+        Ok(())
+    }
+}
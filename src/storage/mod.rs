@@ -1,8 +1,31 @@
+//! Persistence for transfers and computed user statistics.
+//!
+//! [`Storage`] is the facade used by the pipeline. Each backend composes one
+//! implementation of every command ([`SaveTransfersCommand`],
+//! [`SaveStatsCommand`]) and query ([`GetTransfersQuery`], [`GetStatsQuery`]).
+
 pub mod clickhouse;
+pub mod commands;
+pub mod dedup;
 pub mod errors;
-mod storage_trait;
-mod commands;
-mod queries;
+pub mod insert;
+mod legacy;
+pub mod live;
+pub mod memory;
+pub mod migrations;
+pub mod pushdown;
+pub mod queries;
+pub mod rows;
+pub mod storage_trait;
 
 pub use clickhouse::ClickHouseStorage;
+pub use commands::{SaveStatsCommand, SaveTransfersCommand};
+pub use dedup::SaveMode;
+pub use errors::StorageError;
+pub use insert::{ChunkedInsert, InsertConfig, InsertProgress, InsertSummary};
+pub use live::LiveStats;
+pub use memory::InMemoryStorage;
+pub use migrations::Migrator;
+pub use pushdown::ClickHouseStatsBackend;
+pub use queries::{GetStatsQuery, GetTransfersQuery};
 pub use storage_trait::Storage;
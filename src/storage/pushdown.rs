@@ -0,0 +1,116 @@
+//! [`StatsBackend`] computing statistics inside ClickHouse.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clickhouse::Client;
+
+use crate::pipeline::{BalanceWindow, PipelineConfig, StatsBackend};
+use crate::storage::dedup::{save_version, SaveMode};
+
+/// Writes `user_stats` with one `INSERT ... SELECT` over `transfers`, so no
+/// transfer is loaded into memory.
+///
+/// Every transfer becomes a send and a receive event; running balances come
+/// from window functions ordered by `(ts, key)`, the order
+/// [`GetTransfersQuery`](crate::storage::GetTransfersQuery) returns transfers
+/// in. [`PipelineConfig::late_policy`] and `threads` do not apply.
+pub struct ClickHouseStatsBackend {
+    client: Client,
+    mode: SaveMode,
+}
+
+impl ClickHouseStatsBackend {
+    pub fn new(client: Client, mode: SaveMode) -> Self {
+        Self { client, mode }
+    }
+}
+
+#[async_trait]
+impl StatsBackend for ClickHouseStatsBackend {
+    async fn compute_stats(&self, config: &PipelineConfig) -> Result<()> {
+        config.validate()?;
+        if self.mode == SaveMode::Replace {
+            self.client
+                .query("TRUNCATE TABLE user_stats")
+                .execute()
+                .await
+                .context("Failed to truncate user_stats")?;
+        }
+
+        self.client
+            .query(&stats_sql(&config.windows, save_version()))
+            .execute()
+            .await
+            .context("Failed to calculate user stats in ClickHouse")
+    }
+}
+
+/// The `INSERT ... SELECT` statement run by [`ClickHouseStatsBackend`].
+///
+/// A balance counts towards a window when it was still held after the
+/// window start, i.e. the next event of the address (`until`) is later, as
+/// in [`window_max`].
+///
+/// [`window_max`]: crate::pipeline::window_max
+pub fn stats_sql(windows: &[BalanceWindow], version: u64) -> String {
+    let window_maxima: Vec<String> = windows
+        .iter()
+        .map(|w| {
+            let cutoff = format!("if(watermark > {secs}, watermark - {secs}, 0)", secs = w.secs());
+            format!(
+                "'{}', greatest(maxIf(balance, until > {}), toDecimal128(0, 18))",
+                w.label(),
+                cutoff
+            )
+        })
+        .collect();
+
+    format!(
+        r#"
+        INSERT INTO user_stats
+            (address, total_volume, avg_buy_price, avg_sell_price, max_balance,
+             max_balance_windows, version)
+        SELECT
+            address,
+            sum(amount) AS total_volume,
+            if(sumIf(amount, is_buy) > 0,
+                sumIf(usd_price * toFloat64(amount), is_buy) / toFloat64(sumIf(amount, is_buy)),
+                0) AS avg_buy_price,
+            if(sumIf(amount, NOT is_buy) > 0,
+                sumIf(usd_price * toFloat64(amount), NOT is_buy)
+                    / toFloat64(sumIf(amount, NOT is_buy)),
+                0) AS avg_sell_price,
+            greatest(max(balance), toDecimal128(0, 18)) AS max_balance,
+            CAST(map({windows}), 'Map(String, Decimal128(18))') AS max_balance_windows,
+            {version} AS version
+        FROM (
+            SELECT
+                address,
+                amount,
+                usd_price,
+                is_buy,
+                sum(delta) OVER (
+                    PARTITION BY address ORDER BY ts, key, seq
+                    ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+                ) AS balance,
+                leadInFrame(ts, 1, 18446744073709551615) OVER (
+                    PARTITION BY address ORDER BY ts, key, seq
+                    ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING
+                ) AS until,
+                (SELECT max(ts) FROM transfers FINAL) AS watermark
+            FROM (
+                SELECT `from` AS address, ts, key, 0 AS seq, -amount AS delta, amount, usd_price,
+                    0 AS is_buy
+                FROM transfers FINAL
+                UNION ALL
+                SELECT `to` AS address, ts, key, 1 AS seq, amount AS delta, amount, usd_price,
+                    1 AS is_buy
+                FROM transfers FINAL
+            )
+        )
+        GROUP BY address
+        "#,
+        windows = window_maxima.join(", "),
+        version = version,
+    )
+}
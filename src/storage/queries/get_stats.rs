@@ -1,21 +1,50 @@
+use std::cmp::Reverse;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use clickhouse::Client;
+use tokio::sync::RwLock;
 
 use crate::model::UserStats;
 use crate::storage::errors::StorageError;
+use crate::storage::live::SELECT_LIVE_STATS;
+use crate::storage::memory::InMemoryState;
+use crate::storage::rows::UserStatsRow;
 
+/// Reads user statistics ordered by `total_volume` descending.
 #[async_trait]
-pub trait GetStatsQuery {
+pub trait GetStatsQuery: Send + Sync {
     async fn get_stats(&self) -> Result<Vec<UserStats>, StorageError>;
 }
 
+const SELECT_STATS: &str = r#"
+    SELECT
+        address,
+        total_volume,
+        avg_buy_price,
+        avg_sell_price,
+        max_balance,
+        max_balance_windows,
+        version
+    FROM user_stats FINAL
+    ORDER BY total_volume DESC
+"#;
+
+/// Selects from the `user_stats` table, or from the
+/// [live aggregates](crate::storage::live) joined with it.
 pub struct ClickHouseGetStatsQuery {
     client: Client,
+    live: bool,
 }
 
 impl ClickHouseGetStatsQuery {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self { client, live: false }
+    }
+
+    /// Reads volume and prices from `user_stats_agg`.
+    pub fn live(client: Client) -> Self {
+        Self { client, live: true }
     }
 }
 
@@ -23,28 +52,35 @@ impl ClickHouseGetStatsQuery {
 impl GetStatsQuery for ClickHouseGetStatsQuery {
     async fn get_stats(&self) -> Result<Vec<UserStats>, StorageError> {
         let mut cursor = self.client
-            .query(
-                r#"
-                SELECT
-                    address,
-                    total_volume,
-                    avg_buy_price,
-                    avg_sell_price,
-                    max_balance,
-                    max_balance_1h,
-                    max_balance_24h,
-                    max_balance_7d
-                FROM user_stats
-                ORDER BY total_volume DESC
-                "#
-            )
-            .fetch::<UserStats>()
+            .query(if self.live { SELECT_LIVE_STATS } else { SELECT_STATS })
+            .fetch::<UserStatsRow>()
             .map_err(StorageError::ClickHouse)?;
 
         let mut stats = Vec::new();
-        while let Some(stat) = cursor.next().await.map_err(StorageError::ClickHouse)? {
-            stats.push(stat);
+        while let Some(row) = cursor.next().await.map_err(StorageError::ClickHouse)? {
+            stats.push(UserStats::from(row));
         }
         Ok(stats)
     }
 }
+
+/// Sorts a copy of the stats held in [`InMemoryState`].
+#[derive(Clone)]
+pub struct InMemoryGetStatsQuery {
+    state: Arc<RwLock<InMemoryState>>,
+}
+
+impl InMemoryGetStatsQuery {
+    pub fn new(state: Arc<RwLock<InMemoryState>>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl GetStatsQuery for InMemoryGetStatsQuery {
+    async fn get_stats(&self) -> Result<Vec<UserStats>, StorageError> {
+        let mut stats = self.state.read().await.stats.clone();
+        stats.sort_by_key(|s| Reverse(s.total_volume));
+        Ok(stats)
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use clickhouse::Client;
+use tokio::sync::RwLock;
+
+use crate::model::Transfer;
+use crate::storage::errors::StorageError;
+use crate::storage::memory::InMemoryState;
+
+/// Reads stored transfers ordered by `ts` ascending; ties keep a fixed order.
+#[async_trait]
+pub trait GetTransfersQuery: Send + Sync {
+    async fn get_transfers(&self) -> Result<Vec<Transfer>, StorageError>;
+}
+
+/// Selects from the `transfers` table.
+pub struct ClickHouseGetTransfersQuery {
+    client: Client,
+}
+
+impl ClickHouseGetTransfersQuery {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl GetTransfersQuery for ClickHouseGetTransfersQuery {
+    async fn get_transfers(&self) -> Result<Vec<Transfer>, StorageError> {
+        let mut cursor = self.client
+            .query(
+                r#"
+                SELECT
+                    ts,
+                    `from`,
+                    `to`,
+                    amount,
+                    usd_price
+                FROM transfers FINAL
+                ORDER BY ts, key
+                "#
+            )
+            .fetch::<Transfer>()
+            .map_err(StorageError::ClickHouse)?;
+
+        let mut transfers = Vec::new();
+        while let Some(transfer) = cursor.next().await.map_err(StorageError::ClickHouse)? {
+            transfers.push(transfer);
+        }
+        Ok(transfers)
+    }
+}
+
+/// Sorts a copy of the transfers held in [`InMemoryState`].
+#[derive(Clone)]
+pub struct InMemoryGetTransfersQuery {
+    state: Arc<RwLock<InMemoryState>>,
+}
+
+impl InMemoryGetTransfersQuery {
+    pub fn new(state: Arc<RwLock<InMemoryState>>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl GetTransfersQuery for InMemoryGetTransfersQuery {
+    async fn get_transfers(&self) -> Result<Vec<Transfer>, StorageError> {
+        let mut transfers = self.state.read().await.transfers.clone();
+        // Same order as the ClickHouse query.
+        transfers.sort_by_key(|(key, t)| (t.ts, *key));
+        Ok(transfers.into_iter().map(|(_, t)| t).collect())
+    }
+}
@@ -1,3 +1,5 @@
 pub mod get_stats;
+pub mod get_transfers;
 
-pub use get_stats::ClickHouseGetStatsQuery;
+pub use get_stats::{ClickHouseGetStatsQuery, GetStatsQuery, InMemoryGetStatsQuery};
+pub use get_transfers::{ClickHouseGetTransfersQuery, GetTransfersQuery, InMemoryGetTransfersQuery};
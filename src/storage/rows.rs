@@ -0,0 +1,76 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Address, Amount, Transfer, UserStats};
+
+/// ClickHouse layout of [`Transfer`] with its deduplication key, see
+/// [`transfer_keys`](crate::storage::dedup::transfer_keys).
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct TransferRow {
+    pub key: u128,
+    pub ts: u64,
+    pub from: Address,
+    pub to: Address,
+    pub amount: Amount,
+    pub usd_price: f64,
+}
+
+impl TransferRow {
+    pub fn new(key: u128, t: &Transfer) -> Self {
+        Self {
+            key,
+            ts: t.ts,
+            from: t.from,
+            to: t.to,
+            amount: t.amount,
+            usd_price: t.usd_price,
+        }
+    }
+}
+
+/// ClickHouse layout of [`UserStats`].
+///
+/// `Map(String, Decimal128(18))` shares its RowBinary encoding with an array of
+/// pairs, which is what the client can (de)serialize. `version` orders saves of
+/// the same address; the highest one is kept.
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct UserStatsRow {
+    pub address: Address,
+    pub total_volume: Amount,
+    pub avg_buy_price: f64,
+    pub avg_sell_price: f64,
+    pub max_balance: Amount,
+    pub max_balance_windows: Vec<(String, Amount)>,
+    pub version: u64,
+}
+
+impl UserStatsRow {
+    pub fn new(s: &UserStats, version: u64) -> Self {
+        Self {
+            address: s.address,
+            total_volume: s.total_volume,
+            avg_buy_price: s.avg_buy_price,
+            avg_sell_price: s.avg_sell_price,
+            max_balance: s.max_balance,
+            max_balance_windows: s
+                .max_balance_windows
+                .iter()
+                .map(|(label, value)| (label.clone(), *value))
+                .collect(),
+            version,
+        }
+    }
+}
+
+impl From<UserStatsRow> for UserStats {
+    fn from(row: UserStatsRow) -> Self {
+        Self {
+            address: row.address,
+            total_volume: row.total_volume,
+            avg_buy_price: row.avg_buy_price,
+            avg_sell_price: row.avg_sell_price,
+            max_balance: row.max_balance,
+            max_balance_windows: row.max_balance_windows.into_iter().collect(),
+        }
+    }
+}
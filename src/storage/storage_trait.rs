@@ -1,10 +1,25 @@
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use crate::model::{Transfer, UserStats};
 use crate::storage::errors::StorageError;
+use crate::storage::insert::InsertSummary;
 
+/// Backend-agnostic access to persisted transfers and statistics.
 #[async_trait]
-pub trait Storage {
-    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<(), StorageError>;
-    async fn save_stats(&self, stats: &[UserStats]) -> Result<(), StorageError>;
+pub trait Storage: Send + Sync {
+    /// Saves transfers in the backend's [`SaveMode`](crate::storage::SaveMode),
+    /// skipping ones already stored. An empty slice is a no-op.
+    async fn save_transfers(&self, transfers: &[Transfer]) -> Result<InsertSummary, StorageError>;
+    /// Saves a stream of chunks without holding it in memory.
+    async fn save_transfers_stream(
+        &self,
+        chunks: BoxStream<'_, Vec<Transfer>>,
+    ) -> Result<InsertSummary, StorageError>;
+    /// Returns stored transfers ordered by `ts` ascending.
+    async fn get_transfers(&self) -> Result<Vec<Transfer>, StorageError>;
+    /// Saves statistics in the backend's [`SaveMode`](crate::storage::SaveMode);
+    /// the latest save of an address wins. An empty slice is a no-op.
+    async fn save_stats(&self, stats: &[UserStats]) -> Result<InsertSummary, StorageError>;
+    /// Returns stored statistics ordered by `total_volume` descending.
     async fn get_stats(&self) -> Result<Vec<UserStats>, StorageError>;
-}
\ No newline at end of file
+}
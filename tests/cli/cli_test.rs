@@ -0,0 +1,393 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use mycrate::model::Transfer;
+use mycrate::pipeline::{calculate_user_stats_with, PipelineConfig};
+use mycrate::storage::{ChunkedInsert, ClickHouseStorage, SaveMode, Storage};
+
+use crate::storage::TestDatabase;
+
+fn cli() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_token_transfers"))
+}
+
+#[test]
+fn test_generate_writes_json_lines() -> Result<()> {
+    let output = cli()
+        .args(["generate", "-n", "15", "--min-amount", "2", "--max-amount", "3"])
+        .output()
+        .context("Failed to run binary")?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let transfers = stdout
+        .lines()
+        .map(serde_json::from_str::<Transfer>)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(transfers.len(), 15);
+    for transfer in &transfers {
+        assert!(transfer.amount.to_f64() >= 2.0 && transfer.amount.to_f64() < 3.0);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_count_env_fallback() -> Result<()> {
+    let output = cli()
+        .arg("generate")
+        .env("TRANSFERS_COUNT", "7")
+        .output()
+        .context("Failed to run binary")?;
+    assert!(output.status.success());
+
+    assert_eq!(String::from_utf8(output.stdout)?.lines().count(), 7);
+    Ok(())
+}
+
+#[test]
+fn test_run_with_memory_backend() -> Result<()> {
+    let status = cli()
+        .args(["run", "-n", "100", "--backend", "memory"])
+        .status()
+        .context("Failed to run binary")?;
+
+    assert!(status.success());
+    Ok(())
+}
+
+#[test]
+fn test_unknown_subcommand_fails() -> Result<()> {
+    let status = cli().arg("frobnicate").status().context("Failed to run binary")?;
+
+    assert!(!status.success());
+    Ok(())
+}
+
+#[test]
+fn test_run_with_custom_windows() -> Result<()> {
+    let ok = cli()
+        .args(["run", "-n", "50", "--backend", "memory", "--window", "5m,30d,90d"])
+        .status()
+        .context("Failed to run binary")?;
+    assert!(ok.success());
+
+    let invalid = cli()
+        .args(["run", "-n", "50", "--backend", "memory", "--window", "5y"])
+        .status()
+        .context("Failed to run binary")?;
+    assert!(!invalid.success());
+    Ok(())
+}
+
+#[test]
+fn test_seeded_generate_is_reproducible() -> Result<()> {
+    let generate = || {
+        cli()
+            .args(["generate", "-n", "20", "--seed", "7", "--now", "1750000000"])
+            .output()
+            .context("Failed to run binary")
+    };
+
+    let first = generate()?;
+    let second = generate()?;
+
+    assert!(first.status.success());
+    assert_eq!(first.stdout, second.stdout);
+    Ok(())
+}
+
+#[test]
+fn test_split_commands_reject_memory_backend() -> Result<()> {
+    let commands: [&[&str]; 5] = [
+        &["ingest", "-i", "transfers.jsonl"],
+        &["load", "-n", "10"],
+        &["analyze"],
+        &["stats"],
+        &["stats", "export"],
+    ];
+    for command in commands {
+        let output = cli()
+            .args(command)
+            .args(["--backend", "memory"])
+            .output()
+            .context("Failed to run binary")?;
+        assert!(!output.status.success(), "{:?}", command);
+        assert!(String::from_utf8(output.stderr)?.contains("use run instead"), "{:?}", command);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_load_rejects_ledger() -> Result<()> {
+    let ledger = cli()
+        .args(["load", "-n", "10", "--ledger"])
+        .output()
+        .context("Failed to run binary")?;
+    assert!(!ledger.status.success());
+    assert!(String::from_utf8(ledger.stderr)?.contains("ledger generator"));
+    Ok(())
+}
+
+#[test]
+fn test_save_mode_flag() -> Result<()> {
+    let append = cli()
+        .args(["run", "-n", "50", "--backend", "memory", "--save-mode", "append"])
+        .output()
+        .context("Failed to run binary")?;
+    assert!(append.status.success());
+
+    let unknown = cli()
+        .args(["run", "-n", "50", "--backend", "memory", "--save-mode", "upsert"])
+        .output()
+        .context("Failed to run binary")?;
+    assert!(!unknown.status.success());
+    assert!(String::from_utf8(unknown.stderr)?.contains("expected append or replace"));
+    Ok(())
+}
+
+#[test]
+fn test_migrate_needs_clickhouse() -> Result<()> {
+    let output = cli()
+        .args(["migrate", "--backend", "memory"])
+        .output()
+        .context("Failed to run binary")?;
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("only to the ClickHouse backend"));
+    Ok(())
+}
+
+#[test]
+fn test_clickhouse_stats_backend_needs_clickhouse() -> Result<()> {
+    let output = cli()
+        .args(["analyze", "--backend", "memory", "--stats-backend", "clickhouse"])
+        .output()
+        .context("Failed to run binary")?;
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("needs the ClickHouse storage backend"));
+    Ok(())
+}
+
+fn temp_config(name: &str, contents: &str) -> Result<std::path::PathBuf> {
+    let path = std::env::temp_dir().join(format!("token_transfers_{}_{}", std::process::id(), name));
+    std::fs::write(&path, contents).context("Failed to write config")?;
+    Ok(path)
+}
+
+#[test]
+fn test_run_with_example_configs() -> Result<()> {
+    for preset in ["config/example.toml", "config/example.yaml"] {
+        let status = cli()
+            .args(["--config", preset, "run"])
+            .status()
+            .context("Failed to run binary")?;
+        assert!(status.success(), "{} failed", preset);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_config_layering() -> Result<()> {
+    let config = temp_config(
+        "layering.toml",
+        "count = 6\n[generator]\nmin_amount = 40.0\nmax_amount = 50.0\n",
+    )?;
+    let generate = |env_count: Option<&str>, args: &[&str]| -> Result<Vec<Transfer>> {
+        let mut command = cli();
+        command.arg("--config").arg(&config).arg("generate").args(args);
+        command.env_remove("TRANSFERS_COUNT");
+        if let Some(count) = env_count {
+            command.env("TRANSFERS_COUNT", count);
+        }
+        let output = command.output().context("Failed to run binary")?;
+        assert!(output.status.success());
+
+        String::from_utf8(output.stdout)?
+            .lines()
+            .map(|line| serde_json::from_str(line).context("Invalid transfer"))
+            .collect()
+    };
+
+    let from_file = generate(None, &[])?;
+    assert_eq!(from_file.len(), 6);
+    assert!(from_file.iter().all(|t| t.amount.to_f64() >= 40.0 && t.amount.to_f64() < 50.0));
+
+    assert_eq!(generate(Some("4"), &[])?.len(), 4);
+    assert_eq!(generate(Some("4"), &["-n", "2"])?.len(), 2);
+
+    let overridden = generate(None, &["--max-amount", "41"])?;
+    assert!(overridden.iter().all(|t| t.amount.to_f64() >= 40.0 && t.amount.to_f64() < 41.0));
+
+    std::fs::remove_file(config)?;
+    Ok(())
+}
+
+#[test]
+fn test_invalid_config_fails() -> Result<()> {
+    let unknown = temp_config("unknown.toml", "[generator]\nmin_amout = 1.0\n")?;
+    let format = temp_config("format.ini", "count = 1\n")?;
+
+    for config in [&unknown, &format] {
+        let status = cli()
+            .arg("--config")
+            .arg(config)
+            .arg("generate")
+            .status()
+            .context("Failed to run binary")?;
+        assert!(!status.success());
+        std::fs::remove_file(config)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ingest_csv_reports_bad_rows() -> Result<()> {
+    let a = "0x000000000000000000000000000000000000000a";
+    let b = "0x000000000000000000000000000000000000000b";
+    let input = temp_config(
+        "export.csv",
+        &format!(
+            "time,sender,recipient,qty,price\n\
+             2023-11-14T22:13:20Z,{a},{b},1,1\n\
+             not-a-date,{a},{b},1,1\n"
+        ),
+    )?;
+    let args = [
+        "ingest", "--dry-run", "--ts-column", "time", "--from-column", "sender",
+        "--to-column", "recipient", "--amount-column", "qty", "--price-column", "price",
+    ];
+
+    let strict = cli()
+        .args(args)
+        .arg("-i")
+        .arg(&input)
+        .output()
+        .context("Failed to run binary")?;
+    assert!(!strict.status.success());
+    assert!(String::from_utf8(strict.stderr)?.contains("line 3, column 'time'"));
+
+    let output = cli()
+        .args(args)
+        .args(["--max-errors", "1", "-i"])
+        .arg(&input)
+        .output()
+        .context("Failed to run binary")?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("line 3, column 'time'"));
+
+    std::fs::remove_file(input)?;
+    Ok(())
+}
+
+#[test]
+fn test_ingest_erc20_logs() -> Result<()> {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/erc20");
+
+    let output = cli()
+        .args(["ingest", "--dry-run", "--format", "erc20-logs", "--decimals", "6"])
+        .args(["--token", "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "--usd-price", "1"])
+        .args(["--max-errors", "10", "-i"])
+        .arg(fixtures.join("logs.json"))
+        .arg("--blocks")
+        .arg(fixtures.join("blocks.json"))
+        .output()
+        .context("Failed to run binary")?;
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Прочитано 3 трансферов"));
+    assert!(stderr.contains("line 4, column 'topics'"));
+
+    let missing_price = cli()
+        .args(["ingest", "--dry-run", "--format", "erc20-logs", "-i"])
+        .arg(fixtures.join("logs.json"))
+        .status()
+        .context("Failed to run binary")?;
+    assert!(!missing_price.success());
+    Ok(())
+}
+
+#[test]
+fn test_run_exports_stats() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("token_transfers_{}_stats.ndjson", std::process::id()));
+    let status = cli()
+        .args(["run", "-n", "100", "--backend", "memory", "--export"])
+        .arg(&path)
+        .status()
+        .context("Failed to run binary")?;
+    assert!(status.success());
+
+    let rows: Vec<serde_json::Value> = std::fs::read_to_string(&path)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    std::fs::remove_file(&path)?;
+
+    assert!(!rows.is_empty());
+    let volumes: Vec<f64> = rows.iter().filter_map(|r| r["total_volume"].as_f64()).collect();
+    assert!(volumes.windows(2).all(|w| w[0] >= w[1]));
+    Ok(())
+}
+
+/// Appends two generated batches with `run` and checks that the stats of the
+/// second run cover both. Needs a ClickHouse server at `CLICKHOUSE_TEST_URL`
+/// and skips without one.
+#[tokio::test]
+async fn test_run_twice_appends_stats() -> Result<()> {
+    let Some(database) = TestDatabase::create("run_twice").await? else {
+        return Ok(());
+    };
+
+    let result = run_twice(&database).await;
+    database.drop().await?;
+    result
+}
+
+async fn run_twice(database: &TestDatabase) -> Result<()> {
+    let url = std::env::var("CLICKHOUSE_TEST_URL")?;
+    let storage_args = ["--storage-url", &url, "--storage-database", database.name()];
+
+    let migrate = cli()
+        .arg("migrate")
+        .args(storage_args)
+        .status()
+        .context("Failed to run binary")?;
+    assert!(migrate.success());
+    for seed in ["1", "2"] {
+        let status = cli()
+            .args(["run", "-n", "100", "--seed", seed, "--window", "24h"])
+            .args(["--save-mode", "append"])
+            .args(storage_args)
+            .status()
+            .context("Failed to run binary")?;
+        assert!(status.success());
+    }
+
+    let storage = ClickHouseStorage::with_options(
+        database.client.clone(),
+        ChunkedInsert::default(),
+        SaveMode::Append,
+    )
+    .await?;
+    let transfers = storage.get_transfers().await?;
+    assert_eq!(transfers.len(), 200);
+
+    let config = PipelineConfig {
+        windows: vec!["24h".parse()?],
+        ..PipelineConfig::default()
+    };
+    let mut expected = calculate_user_stats_with(&transfers, &config)?;
+    let mut saved = storage.get_stats().await?;
+    expected.sort_by_key(|s| s.address);
+    saved.sort_by_key(|s| s.address);
+    assert_eq!(saved.len(), expected.len());
+    for (saved, expected) in saved.iter().zip(&expected) {
+        assert_eq!(saved.address, expected.address);
+        assert_eq!(saved.total_volume, expected.total_volume);
+        assert_eq!(saved.max_balance_windows, expected.max_balance_windows);
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use anyhow::Result;
+
+use mycrate::export::{export_stats, ExportFormat, ExportOptions, SortOrder, StatsColumn};
+use mycrate::model::Amount;
+
+use super::sample_stats;
+use crate::model::addr;
+
+fn export_csv(options: &ExportOptions) -> Result<String> {
+    let mut out = Vec::new();
+    export_stats(&mut out, &sample_stats(), ExportFormat::Csv, options)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn test_all_columns_by_volume() -> Result<()> {
+    let csv = export_csv(&ExportOptions::default())?;
+
+    assert_eq!(
+        csv,
+        "\
+address,total_volume,avg_buy_price,avg_sell_price,max_balance,max_balance_1h,max_balance_24h
+0x0000000000000000000000000000000000000061,30,1.5,2,15,3,4
+0x0000000000000000000000000000000000000063,20,1.5,2,10,,6
+0x0000000000000000000000000000000000000062,10,1.5,2,5,1,2
+"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_column_selection_and_sorting() -> Result<()> {
+    let options = ExportOptions {
+        columns: vec![StatsColumn::MaxBalanceWindow("24h".to_string()), StatsColumn::Address],
+        sort: SortOrder {
+            column: StatsColumn::Address,
+            descending: false,
+        },
+        limit: Some(2),
+    };
+
+    let (a, b) = (addr("a"), addr("b"));
+    assert_eq!(export_csv(&options)?, format!("max_balance_24h,address\n4,{a}\n2,{b}\n"));
+    Ok(())
+}
+
+#[test]
+fn test_empty_stats_write_header() -> Result<()> {
+    let options = ExportOptions {
+        columns: vec![StatsColumn::Address, StatsColumn::MaxBalanceWindow("24h".to_string())],
+        ..ExportOptions::default()
+    };
+
+    let mut out = Vec::new();
+    export_stats(&mut out, &[], ExportFormat::Csv, &options)?;
+    assert_eq!(String::from_utf8(out)?, "address,max_balance_24h\n");
+    Ok(())
+}
+
+#[test]
+fn test_sort_by_window_puts_missing_last_when_descending() -> Result<()> {
+    let options = ExportOptions {
+        columns: vec![StatsColumn::Address],
+        sort: SortOrder {
+            column: StatsColumn::MaxBalanceWindow("1h".to_string()),
+            descending: true,
+        },
+        limit: None,
+    };
+
+    let (a, b, c) = (addr("a"), addr("b"), addr("c"));
+    assert_eq!(export_csv(&options)?, format!("address\n{a}\n{b}\n{c}\n"));
+    Ok(())
+}
+
+#[test]
+fn test_column_parsing() -> Result<()> {
+    assert_eq!("total_volume".parse::<StatsColumn>()?, StatsColumn::TotalVolume);
+    assert_eq!(
+        "max_balance_30d".parse::<StatsColumn>()?,
+        StatsColumn::MaxBalanceWindow("30d".to_string())
+    );
+    assert!("max_balance_".parse::<StatsColumn>().is_err());
+    assert!("volume".parse::<StatsColumn>().is_err());
+
+    for column in StatsColumn::FIXED {
+        assert_eq!(column.to_string().parse::<StatsColumn>()?, column);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_format_from_path() {
+    use std::path::Path;
+
+    assert_eq!(ExportFormat::from_path(Path::new("out.csv")), Some(ExportFormat::Csv));
+    assert_eq!(ExportFormat::from_path(Path::new("out.jsonl")), Some(ExportFormat::Ndjson));
+    assert_eq!(ExportFormat::from_path(Path::new("out.parquet")), Some(ExportFormat::Parquet));
+    assert_eq!(ExportFormat::from_path(Path::new("out.txt")), None);
+}
+
+#[cfg(not(feature = "parquet"))]
+#[test]
+fn test_parquet_needs_feature() {
+    let result = export_stats(
+        Vec::new(),
+        &sample_stats(),
+        ExportFormat::Parquet,
+        &ExportOptions::default(),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_window_columns_ordered_by_length() -> Result<()> {
+    let mut stats = sample_stats();
+    for (label, units) in [("7d", 9), ("5m", 7)] {
+        stats[0].max_balance_windows.insert(label.to_string(), Amount::from_units(units));
+    }
+
+    let mut out = Vec::new();
+    export_stats(&mut out, &stats, ExportFormat::Csv, &ExportOptions::default())?;
+    let csv = String::from_utf8(out)?;
+
+    let header = csv.lines().next().unwrap_or_default();
+    assert!(header.ends_with("max_balance_5m,max_balance_1h,max_balance_24h,max_balance_7d"));
+    Ok(())
+}
+
+#[test]
+fn test_amounts_are_exact() -> Result<()> {
+    let mut stats = sample_stats();
+    stats[0].total_volume = "123456789.123456789123456789".parse::<Amount>()?;
+    let options = ExportOptions {
+        columns: vec![StatsColumn::TotalVolume, StatsColumn::AvgBuyPrice],
+        ..ExportOptions::default()
+    };
+
+    let mut out = Vec::new();
+    export_stats(&mut out, &stats[..1], ExportFormat::Csv, &options)?;
+    assert_eq!(
+        String::from_utf8(out)?,
+        "total_volume,avg_buy_price\n123456789.123456789123456789,1.5\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use mycrate::export::{export_stats, ExportFormat, ExportOptions, StatsColumn};
+use mycrate::model::Amount;
+
+use super::sample_stats;
+use crate::model::addr;
+
+fn export(format: ExportFormat, options: &ExportOptions) -> Result<String> {
+    let mut out = Vec::new();
+    export_stats(&mut out, &sample_stats(), format, options)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn test_pretty_json_array() -> Result<()> {
+    let output = export(ExportFormat::Json, &ExportOptions::default())?;
+    let first = format!("\n  {{\n    \"address\": \"{}\"", addr("a"));
+    assert!(output.contains(&first));
+
+    let rows: Vec<Value> = serde_json::from_str(&output)?;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(
+        rows[1],
+        json!({
+            "address": addr("c").to_string(),
+            "total_volume": "20",
+            "avg_buy_price": 1.5,
+            "avg_sell_price": 2.0,
+            "max_balance": "10",
+            "max_balance_1h": null,
+            "max_balance_24h": "6",
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ndjson_keeps_column_order() -> Result<()> {
+    let options = ExportOptions {
+        columns: vec![StatsColumn::TotalVolume, StatsColumn::Address],
+        ..ExportOptions::default()
+    };
+    let output = export(ExportFormat::Ndjson, &options)?;
+
+    let lines: Vec<String> = output.lines().map(str::to_string).collect();
+    assert_eq!(
+        lines,
+        [
+            format!(r#"{{"total_volume":"30","address":"{}"}}"#, addr("a")),
+            format!(r#"{{"total_volume":"20","address":"{}"}}"#, addr("c")),
+            format!(r#"{{"total_volume":"10","address":"{}"}}"#, addr("b")),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_amounts_are_exact() -> Result<()> {
+    let mut stats = sample_stats();
+    stats[0].total_volume = "123456789.123456789123456789".parse::<Amount>()?;
+
+    let mut out = Vec::new();
+    export_stats(&mut out, &stats[..1], ExportFormat::Ndjson, &ExportOptions::default())?;
+    let row: Value = serde_json::from_slice(&out)?;
+    assert_eq!(row["total_volume"], "123456789.123456789123456789");
+    Ok(())
+}
@@ -0,0 +1,33 @@
+#[cfg(test)]
+pub mod csv_test;
+
+#[cfg(test)]
+pub mod json_test;
+
+#[cfg(all(test, feature = "parquet"))]
+pub mod parquet_test;
+
+#[cfg(test)]
+pub(crate) fn sample_stats() -> Vec<mycrate::model::UserStats> {
+    use mycrate::model::{Amount, UserStats};
+
+    use crate::model::addr;
+
+    let stats = |address: &str, total_volume: i64, windows: &[(&str, i64)]| UserStats {
+        address: addr(address),
+        total_volume: Amount::from_units(total_volume),
+        avg_buy_price: 1.5,
+        avg_sell_price: 2.0,
+        max_balance: Amount::from_units(total_volume / 2),
+        max_balance_windows: windows
+            .iter()
+            .map(|&(w, v)| (w.to_string(), Amount::from_units(v)))
+            .collect(),
+    };
+
+    vec![
+        stats("b", 10, &[("1h", 1), ("24h", 2)]),
+        stats("a", 30, &[("1h", 3), ("24h", 4)]),
+        stats("c", 20, &[("24h", 6)]),
+    ]
+}
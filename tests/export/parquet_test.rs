@@ -0,0 +1,50 @@
+use std::fs::File;
+
+use anyhow::{Context, Result};
+use arrow_array::{Array, Decimal128Array, Float64Array, StringArray};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use mycrate::export::{export_stats, ExportFormat, ExportOptions};
+use mycrate::model::Amount;
+
+use super::sample_stats;
+use crate::model::addr;
+
+#[test]
+fn test_parquet_round_trip() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("stats_{}.parquet", std::process::id()));
+    export_stats(
+        File::create(&path)?,
+        &sample_stats(),
+        ExportFormat::Parquet,
+        &ExportOptions::default(),
+    )?;
+
+    let mut reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path)?)?.build()?;
+    let batch = reader.next().context("Empty parquet file")??;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(batch.num_rows(), 3);
+    assert_eq!(batch.num_columns(), 7);
+
+    let addresses = batch
+        .column_by_name("address")
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+        .context("address column")?;
+    assert_eq!(addresses.value(0), addr("a").to_string());
+
+    let window = batch
+        .column_by_name("max_balance_1h")
+        .and_then(|c| c.as_any().downcast_ref::<Decimal128Array>())
+        .context("max_balance_1h column")?;
+    assert_eq!((window.precision(), window.scale()), (38, 18));
+    assert_eq!(window.value(0), Amount::from_units(3).raw());
+    assert!(window.is_null(1));
+
+    let price = batch
+        .column_by_name("avg_buy_price")
+        .and_then(|c| c.as_any().downcast_ref::<Float64Array>())
+        .context("avg_buy_price column")?;
+    assert_eq!(price.value(0), 1.5);
+    Ok(())
+}
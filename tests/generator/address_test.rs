@@ -4,13 +4,13 @@ use mycrate::generator::address::rand_address;
 #[test]
 fn test_address_format() {
     let mut rng = rand::rng();
-    let addr = rand_address(&mut rng);
+    let addr = rand_address(&mut rng).to_string();
 
     assert!(addr.starts_with("0x"));
     assert_eq!(addr.len(), 42); // 0x + 40 chars
 
     let hex_part = &addr[2..];
-    assert!(hex_part.chars().all(|c| c.is_digit(16)));
+    assert!(hex_part.chars().all(|c| c.is_ascii_hexdigit()));
 }
 
 #[test]
@@ -29,8 +29,8 @@ fn test_address_uniqueness() {
 #[test]
 fn test_address_randomness() {
     let mut rng = rand::rng();
-    let addr1 = rand_address(&mut rng);
-    let addr2 = rand_address(&mut rng);
+    let addr1 = rand_address(&mut rng).to_string();
+    let addr2 = rand_address(&mut rng).to_string();
 
     assert_ne!(addr1, addr2);
 
@@ -1,4 +1,10 @@
 use mycrate::generator::config::TransferGenConfig;
+use mycrate::generator::errors::ConfigError;
+use mycrate::generator::ledger::{LedgerConfig, LedgerTransferGenerator};
+use mycrate::generator::pool::AddressPoolConfig;
+use mycrate::generator::price::PriceProcessConfig;
+use mycrate::generator::timing::{Arrival, TimePatternConfig};
+use mycrate::generator::transfer::DefaultTransferGenerator;
 
 #[test]
 fn test_default_config() {
@@ -9,6 +15,8 @@ fn test_default_config() {
     assert_eq!(config.min_price, 0.1);
     assert_eq!(config.max_price, 2.0);
     assert_eq!(config.max_age_secs, 86_400 * 30); // 30 days
+    assert_eq!(config.seed, None);
+    assert_eq!(config.now, None);
 }
 
 #[test]
@@ -31,6 +39,7 @@ fn test_custom_config() {
         min_price: 1.0,
         max_price: 3.0,
         max_age_secs: 3600,
+        ..TransferGenConfig::default()
     };
 
     assert_eq!(config.min_amount, 5.0);
@@ -39,3 +48,178 @@ fn test_custom_config() {
     assert_eq!(config.max_price, 3.0);
     assert_eq!(config.max_age_secs, 3600);
 }
+
+#[test]
+fn test_default_config_is_valid() {
+    assert!(TransferGenConfig::default().validate().is_ok());
+}
+
+#[test]
+fn test_empty_amount_range() {
+    let config = TransferGenConfig {
+        min_amount: 10.0,
+        max_amount: 10.0,
+        ..TransferGenConfig::default()
+    };
+
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::EmptyRange { field: "amount", .. })
+    ));
+}
+
+#[test]
+fn test_zero_min_amount() {
+    let config = TransferGenConfig {
+        min_amount: 0.0,
+        max_amount: 10.0,
+        ..TransferGenConfig::default()
+    };
+
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_invalid_price_bounds() {
+    let inverted = TransferGenConfig {
+        min_price: 3.0,
+        max_price: 1.0,
+        ..TransferGenConfig::default()
+    };
+    assert!(matches!(
+        inverted.validate(),
+        Err(ConfigError::EmptyRange { field: "price", .. })
+    ));
+
+    let negative = TransferGenConfig {
+        min_price: -1.0,
+        ..TransferGenConfig::default()
+    };
+    assert!(matches!(
+        negative.validate(),
+        Err(ConfigError::InvalidValue { field: "price", .. })
+    ));
+
+    let infinite = TransferGenConfig {
+        max_price: f64::INFINITY,
+        ..TransferGenConfig::default()
+    };
+    assert!(infinite.validate().is_err());
+}
+
+#[test]
+fn test_max_age_before_epoch() {
+    let config = TransferGenConfig {
+        now: Some(1_000),
+        max_age_secs: 1_001,
+        ..TransferGenConfig::default()
+    };
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::MaxAgeBeforeEpoch { max_age_secs: 1_001, end: 1_000 })
+    ));
+
+    let explicit_start = TransferGenConfig {
+        start_ts: Some(0),
+        ..config
+    };
+    assert!(explicit_start.validate().is_ok());
+}
+
+#[test]
+fn test_start_after_end() {
+    let config = TransferGenConfig {
+        start_ts: Some(2_000),
+        end_ts: Some(1_000),
+        ..TransferGenConfig::default()
+    };
+
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::TimeRange { start: 2_000, end: 1_000 })
+    ));
+}
+
+#[test]
+fn test_nested_configs_are_validated() {
+    let pool = TransferGenConfig {
+        pool: Some(AddressPoolConfig {
+            mint_share: 1.5,
+            ..AddressPoolConfig::default()
+        }),
+        ..TransferGenConfig::default()
+    };
+    assert!(matches!(
+        pool.validate(),
+        Err(ConfigError::InvalidValue { field: "mint_share", .. })
+    ));
+
+    let price = TransferGenConfig {
+        price_process: Some(PriceProcessConfig {
+            volatility: -0.1,
+            ..PriceProcessConfig::default()
+        }),
+        ..TransferGenConfig::default()
+    };
+    assert!(matches!(
+        price.validate(),
+        Err(ConfigError::InvalidValue { field: "volatility", .. })
+    ));
+
+    let timing = TransferGenConfig {
+        time_pattern: TimePatternConfig {
+            arrival: Arrival::Poisson { rate: f64::NAN },
+            ..TimePatternConfig::default()
+        },
+        ..TransferGenConfig::default()
+    };
+    assert!(matches!(
+        timing.validate(),
+        Err(ConfigError::InvalidValue { field: "poisson_rate", .. })
+    ));
+}
+
+#[test]
+fn test_generators_reject_invalid_config() {
+    let config = TransferGenConfig {
+        min_amount: 5.0,
+        max_amount: 1.0,
+        ..TransferGenConfig::default()
+    };
+    assert!(DefaultTransferGenerator::new(config.clone()).is_err());
+    assert!(LedgerTransferGenerator::new(config, LedgerConfig::default()).is_err());
+
+    let ledger = LedgerConfig {
+        mint_share: -0.1,
+        ..LedgerConfig::default()
+    };
+    assert!(LedgerTransferGenerator::new(TransferGenConfig::default(), ledger).is_err());
+}
+
+#[test]
+fn test_config_deserialization() -> Result<(), Box<dyn std::error::Error>> {
+    let config: TransferGenConfig = toml::from_str(
+        r#"
+        seed = 9
+        min_amount = 2.0
+
+        [pool]
+        size = 50
+
+        [time_pattern]
+        arrival = { kind = "poisson", rate = 0.5 }
+        "#,
+    )?;
+
+    assert_eq!(config.seed, Some(9));
+    assert_eq!(config.min_amount, 2.0);
+    assert_eq!(config.max_amount, TransferGenConfig::default().max_amount);
+    assert_eq!(config.pool.map(|p| p.size), Some(50));
+    assert_eq!(config.time_pattern.arrival, Arrival::Poisson { rate: 0.5 });
+
+    let yaml: TransferGenConfig = serde_yaml::from_str("price_process:\n  volatility: 0.3\n")?;
+    assert_eq!(yaml.price_process.map(|p| p.volatility), Some(0.3));
+
+    assert!(toml::from_str::<TransferGenConfig>("min_amout = 2.0").is_err());
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use mycrate::generator::{
+    config::TransferGenConfig,
+    errors::ConfigError,
+    ledger::{LedgerConfig, LedgerTransferGenerator},
+    pool::{AddressPoolConfig, MINT_ADDRESS},
+    transfer::TransferGenerator,
+};
+use mycrate::model::{Address, Amount, Transfer};
+
+fn generate(config: TransferGenConfig, ledger: LedgerConfig, count: usize) -> Result<Vec<Transfer>> {
+    LedgerTransferGenerator::new(config, ledger)?.generate(count)
+}
+
+fn seeded(seed: u64) -> TransferGenConfig {
+    TransferGenConfig {
+        seed: Some(seed),
+        pool: Some(AddressPoolConfig {
+            size: 100,
+            ..AddressPoolConfig::default()
+        }),
+        ..TransferGenConfig::default()
+    }
+}
+
+#[test]
+fn test_balances_never_negative() -> Result<()> {
+    let transfers = generate(seeded(1), LedgerConfig::default(), 5_000)?;
+
+    let mut balances: HashMap<Address, Amount> = HashMap::new();
+    for t in &transfers {
+        *balances.entry(t.from).or_default() -= t.amount;
+        *balances.entry(t.to).or_default() += t.amount;
+
+        if t.from != MINT_ADDRESS {
+            assert!(balances[&t.from] >= Amount::ZERO, "{} overspent", t.from);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_tiny_skewed_pool() -> Result<()> {
+    let pool = AddressPoolConfig {
+        size: 3,
+        zipf_exponent: 1000.0,
+        exchanges: 0,
+        whales: 0,
+        ..AddressPoolConfig::default()
+    };
+    let config = TransferGenConfig {
+        pool: Some(pool.clone()),
+        ..seeded(1)
+    };
+
+    let transfers = generate(config, LedgerConfig::default(), 200)?;
+    assert_eq!(transfers.len(), 200);
+    assert!(transfers.iter().all(|t| t.from != t.to && t.to != MINT_ADDRESS));
+
+    let two = TransferGenConfig {
+        pool: Some(AddressPoolConfig { size: 2, ..pool }),
+        ..seeded(1)
+    };
+    assert!(matches!(
+        LedgerTransferGenerator::new(two, LedgerConfig::default()),
+        Err(ConfigError::LedgerPoolTooSmall { size: 2 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_supply_starts_with_mint() -> Result<()> {
+    let transfers = generate(seeded(2), LedgerConfig::default(), 100)?;
+
+    assert_eq!(transfers[0].from, MINT_ADDRESS);
+    assert!(transfers.iter().all(|t| t.to != MINT_ADDRESS));
+    assert!(transfers.iter().any(|t| t.from != MINT_ADDRESS));
+    Ok(())
+}
+
+#[test]
+fn test_sorted_by_timestamp() -> Result<()> {
+    let transfers = generate(seeded(3), LedgerConfig::default(), 1_000)?;
+
+    assert!(transfers.windows(2).all(|w| w[0].ts <= w[1].ts));
+    Ok(())
+}
+
+#[test]
+fn test_custom_genesis_address() -> Result<()> {
+    let genesis: Address = "0x00000000000000000000000000000000000000aa".parse()?;
+    let ledger = LedgerConfig {
+        genesis_address: genesis,
+        mint_share: 0.2,
+    };
+
+    let transfers = generate(seeded(4), ledger, 500)?;
+
+    assert!(transfers.iter().all(|t| t.from != MINT_ADDRESS));
+    assert!(transfers.iter().any(|t| t.from == genesis));
+    assert!(transfers.iter().all(|t| t.to != genesis));
+    Ok(())
+}
+
+#[test]
+fn test_amounts_within_bounds() -> Result<()> {
+    let config = TransferGenConfig {
+        min_amount: 5.0,
+        max_amount: 50.0,
+        ..seeded(5)
+    };
+
+    let transfers = generate(config.clone(), LedgerConfig::default(), 1_000)?;
+
+    for t in &transfers {
+        assert!(t.amount.to_f64() >= config.min_amount);
+        assert!(t.amount.to_f64() <= config.max_amount);
+        assert_ne!(t.from, t.to);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_seeded_ledger_is_reproducible() -> Result<()> {
+    let config = TransferGenConfig {
+        now: Some(1_750_000_000),
+        ..seeded(6)
+    };
+
+    let first = generate(config.clone(), LedgerConfig::default(), 300)?;
+    let second = generate(config, LedgerConfig::default(), 300)?;
+
+    assert_eq!(serde_json::to_vec(&first)?, serde_json::to_vec(&second)?);
+    Ok(())
+}
@@ -4,8 +4,23 @@ pub mod address_test;
 #[cfg(test)]
 pub mod config_test;
 
+#[cfg(test)]
+pub mod ledger_test;
+
+#[cfg(test)]
+pub mod pool_test;
+
+#[cfg(test)]
+pub mod price_test;
+
+#[cfg(test)]
+pub mod timing_test;
+
 #[cfg(test)]
 pub mod transfer_test;
 
 #[cfg(test)]
 pub mod integration_test;
+
+#[cfg(test)]
+pub mod stream_test;
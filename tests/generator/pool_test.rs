@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use mycrate::generator::{
+    config::TransferGenConfig,
+    pool::{AddressPool, AddressPoolConfig, AddressRole, MINT_ADDRESS},
+    transfer::{DefaultTransferGenerator, TransferGenerator},
+};
+use mycrate::model::Address;
+use mycrate::pipeline::calculate_user_stats;
+
+fn pooled_config(pool: AddressPoolConfig) -> TransferGenConfig {
+    TransferGenConfig {
+        seed: Some(11),
+        pool: Some(pool),
+        ..TransferGenConfig::default()
+    }
+}
+
+#[test]
+fn test_addresses_are_bounded_by_pool() -> Result<()> {
+    let pool = AddressPoolConfig {
+        size: 50,
+        ..AddressPoolConfig::default()
+    };
+    let max_addresses = pool.size + pool.exchanges + pool.whales + 1;
+
+    let transfers = DefaultTransferGenerator::new(pooled_config(pool))?.generate(5_000)?;
+
+    let mut counts: HashMap<Address, usize> = HashMap::new();
+    for t in &transfers {
+        assert_ne!(t.from, t.to);
+        assert_ne!(t.to, MINT_ADDRESS);
+        *counts.entry(t.from).or_default() += 1;
+        *counts.entry(t.to).or_default() += 1;
+    }
+
+    assert!(counts.len() <= max_addresses);
+    assert!(counts.values().any(|&c| c > 1));
+    Ok(())
+}
+
+#[test]
+fn test_popularity_is_skewed() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let pool = AddressPool::new(
+        AddressPoolConfig {
+            size: 200,
+            exchange_share: 0.0,
+            whale_share: 0.0,
+            ..AddressPoolConfig::default()
+        },
+        &mut rng,
+    )?;
+
+    let mut counts: HashMap<Address, usize> = HashMap::new();
+    for _ in 0..10_000 {
+        *counts.entry(pool.pick(&mut rng)).or_default() += 1;
+    }
+
+    let top = counts.get(&pool.regular()[0]).copied().unwrap_or_default();
+    let tail = counts.get(&pool.regular()[199]).copied().unwrap_or_default();
+    assert!(top > 10 * tail.max(1));
+    Ok(())
+}
+
+#[test]
+fn test_roles() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(5);
+    let pool = AddressPool::new(AddressPoolConfig::default(), &mut rng)?;
+
+    assert_eq!(pool.exchanges().len(), 5);
+    assert_eq!(pool.whales().len(), 10);
+    assert_eq!(pool.role(MINT_ADDRESS), Some(AddressRole::Mint));
+    assert_eq!(pool.role(pool.exchanges()[0]), Some(AddressRole::Exchange));
+    assert_eq!(pool.role(pool.whales()[0]), Some(AddressRole::Whale));
+    assert_eq!(pool.role(pool.regular()[0]), Some(AddressRole::Regular));
+    assert_eq!(pool.role(Address::from_bytes([0xff; 20])), None);
+    Ok(())
+}
+
+#[test]
+fn test_mint_only_sends() -> Result<()> {
+    let transfers = DefaultTransferGenerator::new(pooled_config(AddressPoolConfig {
+        mint_share: 0.5,
+        ..AddressPoolConfig::default()
+    }))?
+    .generate(1_000)?;
+
+    let minted = transfers.iter().filter(|t| t.from == MINT_ADDRESS).count();
+    assert!(minted > 300 && minted < 700);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_pool_config() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+    let too_small = AddressPoolConfig {
+        size: 1,
+        ..AddressPoolConfig::default()
+    };
+    assert!(AddressPool::new(too_small, &mut rng).is_err());
+
+    let bad_shares = AddressPoolConfig {
+        exchange_share: 0.7,
+        whale_share: 0.5,
+        ..AddressPoolConfig::default()
+    };
+    assert!(AddressPool::new(bad_shares, &mut rng).is_err());
+}
+
+#[test]
+fn test_single_exchange_takes_all_traffic() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+    let only_exchange = AddressPoolConfig {
+        exchanges: 1,
+        exchange_share: 1.0,
+        whale_share: 0.0,
+        ..AddressPoolConfig::default()
+    };
+    assert!(AddressPool::new(only_exchange.clone(), &mut rng).is_err());
+
+    let pool = AddressPool::new(
+        AddressPoolConfig {
+            exchange_share: 0.999_999,
+            ..only_exchange
+        },
+        &mut rng,
+    )?;
+    for _ in 0..1_000 {
+        let (from, to) = pool.pick_pair(&mut rng);
+        assert_ne!(from, to);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_pick_except_falls_back_to_regular() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+    let pool = AddressPool::new(
+        AddressPoolConfig {
+            size: 2,
+            zipf_exponent: 1000.0,
+            exchanges: 0,
+            whales: 0,
+            ..AddressPoolConfig::default()
+        },
+        &mut rng,
+    )?;
+    let [first, second] = [pool.regular()[0], pool.regular()[1]];
+
+    assert_eq!(pool.pick_except(&mut rng, &[first]), Some(second));
+    assert_eq!(pool.pick_except(&mut rng, &[first, second]), None);
+    Ok(())
+}
+
+#[test]
+fn test_pooled_data_produces_meaningful_stats() -> Result<()> {
+    let transfers = DefaultTransferGenerator::new(pooled_config(AddressPoolConfig::default()))?
+        .generate(2_000)?;
+    let stats = calculate_user_stats(&transfers)?;
+
+    assert!(stats.len() < transfers.len());
+    assert!(stats.iter().any(|s| s.avg_buy_price > 0.0 && s.avg_sell_price > 0.0));
+    Ok(())
+}
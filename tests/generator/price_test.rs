@@ -0,0 +1,128 @@
+use anyhow::Result;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use mycrate::generator::{
+    config::TransferGenConfig,
+    ledger::{LedgerConfig, LedgerTransferGenerator},
+    price::{PricePath, PriceProcessConfig},
+    transfer::{DefaultTransferGenerator, TransferGenerator},
+};
+
+const YEAR: u64 = 365 * 86_400;
+
+fn flat(config: PriceProcessConfig) -> PriceProcessConfig {
+    PriceProcessConfig {
+        volatility: 0.0,
+        spread: 0.0,
+        ..config
+    }
+}
+
+#[test]
+fn test_constant_without_randomness() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let mut path = PricePath::new(flat(PriceProcessConfig::default()), 0)?;
+
+    for ts in [0, 60, 3_600, YEAR] {
+        assert_eq!(path.price_at(ts, &mut rng), 1.0);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_drift_compounds_over_time() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let config = flat(PriceProcessConfig {
+        initial_price: 2.0,
+        drift: 0.1,
+        ..PriceProcessConfig::default()
+    });
+    let mut path = PricePath::new(config, 0)?;
+
+    let price = path.mid_at(YEAR, &mut rng);
+    assert!((price - 2.0 * 0.1_f64.exp()).abs() < 1e-9);
+    Ok(())
+}
+
+#[test]
+fn test_jumps_move_price() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let config = flat(PriceProcessConfig {
+        jump_intensity: 1_000.0,
+        jump_mean: 0.01,
+        jump_std: 0.0,
+        ..PriceProcessConfig::default()
+    });
+    let mut path = PricePath::new(config, 0)?;
+
+    assert!(path.mid_at(YEAR / 10, &mut rng) > 1.5);
+    Ok(())
+}
+
+#[test]
+fn test_spread_bounds_price_around_mid() -> Result<()> {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let config = PriceProcessConfig {
+        volatility: 0.0,
+        spread: 0.01,
+        ..PriceProcessConfig::default()
+    };
+    let mut path = PricePath::new(config, 0)?;
+
+    for ts in 0..1_000 {
+        let price = path.price_at(ts, &mut rng);
+        assert!((0.99..1.01).contains(&price));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_price_config() {
+    let invalid = [
+        PriceProcessConfig { initial_price: 0.0, ..PriceProcessConfig::default() },
+        PriceProcessConfig { volatility: -1.0, ..PriceProcessConfig::default() },
+        PriceProcessConfig { jump_intensity: -1.0, ..PriceProcessConfig::default() },
+        PriceProcessConfig { jump_std: -1.0, ..PriceProcessConfig::default() },
+        PriceProcessConfig { spread: 1.5, ..PriceProcessConfig::default() },
+    ];
+
+    for config in invalid {
+        assert!(PricePath::new(config, 0).is_err());
+    }
+}
+
+#[test]
+fn test_generated_prices_are_time_consistent() -> Result<()> {
+    let config = TransferGenConfig {
+        seed: Some(9),
+        price_process: Some(PriceProcessConfig::default()),
+        ..TransferGenConfig::default()
+    };
+
+    let mut transfers = DefaultTransferGenerator::new(config)?.generate(5_000)?;
+    transfers.sort_by_key(|t| t.ts);
+
+    for w in transfers.windows(2) {
+        let change = (w[1].usd_price / w[0].usd_price - 1.0).abs();
+        assert!(change < 0.05, "price jumped by {}", change);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ledger_generator_uses_price_process() -> Result<()> {
+    let config = TransferGenConfig {
+        seed: Some(10),
+        price_process: Some(flat(PriceProcessConfig {
+            initial_price: 3.0,
+            ..PriceProcessConfig::default()
+        })),
+        ..TransferGenConfig::default()
+    };
+
+    let transfers = LedgerTransferGenerator::new(config, LedgerConfig::default())?.generate(100)?;
+
+    assert!(transfers.iter().all(|t| t.usd_price == 3.0));
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use futures::StreamExt;
+
+use mycrate::generator::{config::TransferGenConfig, transfer::DefaultTransferGenerator};
+use mycrate::model::Transfer;
+use mycrate::storage::{InMemoryStorage, SaveMode, Storage};
+
+fn seeded(seed: u64) -> Result<DefaultTransferGenerator> {
+    Ok(DefaultTransferGenerator::new(TransferGenConfig {
+        seed: Some(seed),
+        now: Some(1_700_000_000),
+        ..TransferGenConfig::default()
+    })?)
+}
+
+#[test]
+fn test_iter_is_unbounded() -> Result<()> {
+    let transfers: Vec<Transfer> = seeded(1)?.iter()?.skip(50_000).take(5).collect();
+
+    assert_eq!(transfers.len(), 5);
+    Ok(())
+}
+
+#[test]
+fn test_iter_is_deterministic() -> Result<()> {
+    let generator = seeded(7)?;
+    let first: Vec<Transfer> = generator.iter()?.take(100).collect();
+    let second: Vec<Transfer> = generator.iter()?.take(100).collect();
+
+    assert_eq!(
+        serde_json::to_string(&first)?,
+        serde_json::to_string(&second)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_iter_respects_ranges() -> Result<()> {
+    let config = TransferGenConfig {
+        seed: Some(3),
+        now: Some(1_700_000_000),
+        ..TransferGenConfig::default()
+    };
+    let (start, end) = (1_700_000_000 - config.max_age_secs, 1_700_000_000);
+
+    for transfer in DefaultTransferGenerator::new(config.clone())?.iter()?.take(1_000) {
+        assert!(transfer.amount.to_f64() >= config.min_amount && transfer.amount.to_f64() < config.max_amount);
+        assert!(transfer.ts >= start && transfer.ts <= end);
+        assert_ne!(transfer.from, transfer.to);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_chunks() -> Result<()> {
+    let chunks: Vec<Vec<Transfer>> = seeded(1)?.stream(Some(250), 100)?.collect().await;
+
+    let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![100, 100, 50]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_matches_iter() -> Result<()> {
+    let generator = seeded(11)?;
+    let streamed: Vec<Transfer> = generator.stream(Some(300), 64)?.concat().await;
+    let iterated: Vec<Transfer> = generator.iter()?.take(300).collect();
+
+    assert_eq!(
+        serde_json::to_string(&streamed)?,
+        serde_json::to_string(&iterated)?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unbounded_stream_can_be_dropped() -> Result<()> {
+    let mut stream = seeded(2)?.stream(None, 10)?;
+
+    for _ in 0..5 {
+        assert_eq!(stream.next().await.map(|c| c.len()), Some(10));
+    }
+    drop(stream);
+    Ok(())
+}
+
+#[test]
+fn test_stream_rejects_zero_chunk() -> Result<()> {
+    assert!(seeded(1)?.stream(Some(10), 0).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_into_storage() -> Result<()> {
+    let storage = InMemoryStorage::with_mode(SaveMode::Replace);
+    storage.save_transfers(&seeded(4)?.iter()?.take(5).collect::<Vec<_>>()).await?;
+
+    let written = storage
+        .save_transfers_stream(seeded(5)?.stream(Some(1_234), 500)?)
+        .await?;
+
+    assert_eq!(written.rows, 1_234);
+    assert_eq!(storage.get_transfers().await?.len(), 1_234);
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use mycrate::generator::{
+    config::TransferGenConfig,
+    timing::{Arrival, BurstConfig, Seasonality, TimePatternConfig, Timeline},
+    transfer::{DefaultTransferGenerator, TransferGenerator},
+};
+
+const DAY: u64 = 86_400;
+// Monday 2025-05-26 00:00:00 UTC
+const MONDAY: u64 = 1_748_217_600;
+
+fn generate(time_pattern: TimePatternConfig, count: usize) -> Result<Vec<u64>> {
+    let config = TransferGenConfig {
+        seed: Some(21),
+        start_ts: Some(MONDAY),
+        end_ts: Some(MONDAY + 28 * DAY),
+        time_pattern,
+        ..TransferGenConfig::default()
+    };
+    let transfers = DefaultTransferGenerator::new(config)?.generate(count)?;
+    Ok(transfers.iter().map(|t| t.ts).collect())
+}
+
+#[test]
+fn test_explicit_range() -> Result<()> {
+    let config = TransferGenConfig {
+        start_ts: Some(1_000_000),
+        end_ts: Some(1_000_100),
+        ..TransferGenConfig::default()
+    };
+
+    let transfers = DefaultTransferGenerator::new(config)?.generate(500)?;
+
+    assert!(transfers.iter().all(|t| t.ts > 1_000_000 && t.ts <= 1_000_100));
+    Ok(())
+}
+
+#[test]
+fn test_sorted_output() -> Result<()> {
+    let config = TransferGenConfig {
+        sorted: true,
+        ..TransferGenConfig::default()
+    };
+
+    let transfers = DefaultTransferGenerator::new(config)?.generate(500)?;
+
+    assert!(transfers.windows(2).all(|w| w[0].ts <= w[1].ts));
+    Ok(())
+}
+
+#[test]
+fn test_poisson_arrivals() -> Result<()> {
+    let pattern = TimePatternConfig {
+        arrival: Arrival::Poisson { rate: 1.0 / 60.0 },
+        ..TimePatternConfig::default()
+    };
+
+    let ts = generate(pattern, 10_000)?;
+
+    assert!(ts.windows(2).all(|w| w[0] <= w[1]));
+    let mean_gap = (ts[ts.len() - 1] - MONDAY) as f64 / ts.len() as f64;
+    assert!((mean_gap - 60.0).abs() < 3.0, "mean gap {}", mean_gap);
+    Ok(())
+}
+
+#[test]
+fn test_poisson_stops_at_range_end() -> Result<()> {
+    let config = TransferGenConfig {
+        seed: Some(5),
+        start_ts: Some(1_000_000),
+        end_ts: Some(1_003_600),
+        time_pattern: TimePatternConfig {
+            arrival: Arrival::Poisson { rate: 1.0 },
+            ..TimePatternConfig::default()
+        },
+        ..TransferGenConfig::default()
+    };
+
+    let transfers = DefaultTransferGenerator::new(config)?.generate(10_000)?;
+
+    assert!(transfers.len() > 3_000 && transfers.len() < 4_200, "{}", transfers.len());
+    assert!(transfers.iter().all(|t| t.ts >= 1_000_000 && t.ts <= 1_003_600));
+    Ok(())
+}
+
+#[test]
+fn test_poisson_excludes_range_start() -> Result<()> {
+    let pattern = TimePatternConfig {
+        arrival: Arrival::Poisson { rate: 100.0 },
+        ..TimePatternConfig::default()
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let mut timeline = Timeline::new(&pattern, 1_000, 1_002, &mut rng)?;
+
+    let ts: Vec<u64> = std::iter::from_fn(|| timeline.next_ts(&mut rng)).collect();
+
+    assert!(ts.len() > 100, "{}", ts.len());
+    assert!(ts.iter().all(|&t| t > 1_000 && t <= 1_002), "{:?}", &ts[..5]);
+    Ok(())
+}
+
+#[test]
+fn test_bursts_concentrate_activity() -> Result<()> {
+    let pattern = TimePatternConfig {
+        bursts: Some(BurstConfig {
+            count: 1,
+            duration_secs: 3_600,
+            intensity: 200.0,
+        }),
+        ..TimePatternConfig::default()
+    };
+
+    let ts = generate(pattern, 5_000)?;
+
+    let mut hourly = vec![0usize; 28 * 24 + 1];
+    for t in &ts {
+        hourly[((t - MONDAY) / 3_600) as usize] += 1;
+    }
+    let busiest = hourly.windows(2).map(|w| w[0] + w[1]).max().unwrap_or_default();
+    assert!(busiest > ts.len() / 5, "busiest two hours had {}", busiest);
+    Ok(())
+}
+
+#[test]
+fn test_daily_seasonality() -> Result<()> {
+    let pattern = TimePatternConfig {
+        seasonality: Some(Seasonality {
+            daily_amplitude: 0.9,
+            peak_hour: 12,
+            weekly_amplitude: 0.0,
+        }),
+        ..TimePatternConfig::default()
+    };
+
+    let ts = generate(pattern, 20_000)?;
+
+    let hour = |t: &u64| (t % DAY) / 3_600;
+    let midday = ts.iter().filter(|t| (10..14).contains(&hour(t))).count();
+    let midnight = ts.iter().filter(|t| hour(t) >= 22 || hour(t) < 2).count();
+    assert!(midday > 5 * midnight, "midday {} vs midnight {}", midday, midnight);
+    Ok(())
+}
+
+#[test]
+fn test_weekly_seasonality() -> Result<()> {
+    let pattern = TimePatternConfig {
+        seasonality: Some(Seasonality {
+            daily_amplitude: 0.0,
+            peak_hour: 0,
+            weekly_amplitude: 0.8,
+        }),
+        ..TimePatternConfig::default()
+    };
+
+    let ts = generate(pattern, 20_000)?;
+
+    let weekend = ts.iter().filter(|t| ((*t - MONDAY) / DAY) % 7 >= 5).count();
+    let weekday_per_day = (ts.len() - weekend) as f64 / 5.0;
+    let weekend_per_day = weekend as f64 / 2.0;
+    assert!(weekday_per_day > 4.0 * weekend_per_day);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_patterns() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let default = TimePatternConfig::default();
+
+    assert!(Timeline::new(&default, 10, 5, &mut rng).is_err());
+
+    let zero_rate = TimePatternConfig {
+        arrival: Arrival::Poisson { rate: 0.0 },
+        ..TimePatternConfig::default()
+    };
+    assert!(Timeline::new(&zero_rate, 0, 10, &mut rng).is_err());
+
+    let amplitude = TimePatternConfig {
+        seasonality: Some(Seasonality {
+            daily_amplitude: 1.5,
+            peak_hour: 0,
+            weekly_amplitude: 0.0,
+        }),
+        ..TimePatternConfig::default()
+    };
+    assert!(Timeline::new(&amplitude, 0, 10, &mut rng).is_err());
+
+    let weak_burst = TimePatternConfig {
+        bursts: Some(BurstConfig {
+            count: 1,
+            duration_secs: 10,
+            intensity: 0.5,
+        }),
+        ..TimePatternConfig::default()
+    };
+    assert!(Timeline::new(&weak_burst, 0, 10, &mut rng).is_err());
+}
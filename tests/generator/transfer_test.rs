@@ -23,27 +23,27 @@ fn test_generate_transfers_count() -> Result<()> {
 #[test]
 fn test_transfer_generator_basic() -> Result<()> {
     let config = TransferGenConfig::default();
-    let generator = DefaultTransferGenerator::new(config.clone());
+    let generator = DefaultTransferGenerator::new(config.clone())?;
     let transfers = generator.generate(20)?;
 
     assert_eq!(transfers.len(), 20);
 
     for transfer in &transfers {
-        assert!(transfer.amount >= config.min_amount);
-        assert!(transfer.amount <= config.max_amount);
+        assert!(transfer.amount.to_f64() >= config.min_amount);
+        assert!(transfer.amount.to_f64() <= config.max_amount);
 
         assert!(transfer.usd_price >= config.min_price);
         assert!(transfer.usd_price <= config.max_price);
 
-        assert!(transfer.from.starts_with("0x"));
-        assert!(transfer.to.starts_with("0x"));
-        assert_eq!(transfer.from.len(), 42);
-        assert_eq!(transfer.to.len(), 42);
+        assert!(transfer.from.to_string().starts_with("0x"));
+        assert!(transfer.to.to_string().starts_with("0x"));
+        assert_eq!(transfer.from.to_string().len(), 42);
+        assert_eq!(transfer.to.to_string().len(), 42);
         assert_ne!(transfer.from, transfer.to);
 
-        assert!(transfer.amount > 0.0);
+        assert!(transfer.amount.to_f64() > 0.0);
         assert!(transfer.usd_price > 0.0);
-        assert!(transfer.amount.is_finite());
+        assert!(transfer.amount.to_f64().is_finite());
         assert!(transfer.usd_price.is_finite());
     }
 
@@ -53,7 +53,7 @@ fn test_transfer_generator_basic() -> Result<()> {
 #[test]
 fn test_transfer_timestamps() -> Result<()> {
     let config = TransferGenConfig::default();
-    let generator = DefaultTransferGenerator::new(config.clone());
+    let generator = DefaultTransferGenerator::new(config.clone())?;
     let transfers = generator.generate(10)?;
 
     let now = SystemTime::now()
@@ -77,9 +77,10 @@ fn test_custom_config_ranges() -> Result<()> {
         min_price: 1.0,
         max_price: 3.0,
         max_age_secs: 3600,
+        ..TransferGenConfig::default()
     };
 
-    let generator = DefaultTransferGenerator::new(config.clone());
+    let generator = DefaultTransferGenerator::new(config.clone())?;
     let transfers = generator.generate(30)?;
 
     let now = SystemTime::now()
@@ -87,8 +88,8 @@ fn test_custom_config_ranges() -> Result<()> {
         .as_secs();
 
     for transfer in &transfers {
-        assert!(transfer.amount >= config.min_amount);
-        assert!(transfer.amount <= config.max_amount);
+        assert!(transfer.amount.to_f64() >= config.min_amount);
+        assert!(transfer.amount.to_f64() <= config.max_amount);
         assert!(transfer.usd_price >= config.min_price);
         assert!(transfer.usd_price <= config.max_price);
 
@@ -106,14 +107,15 @@ fn test_edge_case_small_ranges() -> Result<()> {
         min_price: 0.5,
         max_price: 0.6,
         max_age_secs: 1,
+        ..TransferGenConfig::default()
     };
 
-    let generator = DefaultTransferGenerator::new(config.clone());
+    let generator = DefaultTransferGenerator::new(config.clone())?;
     let transfers = generator.generate(5)?;
 
     for transfer in &transfers {
-        assert!(transfer.amount >= config.min_amount);
-        assert!(transfer.amount <= config.max_amount);
+        assert!(transfer.amount.to_f64() >= config.min_amount);
+        assert!(transfer.amount.to_f64() <= config.max_amount);
         assert!(transfer.usd_price >= config.min_price);
         assert!(transfer.usd_price <= config.max_price);
     }
@@ -129,9 +131,10 @@ fn test_zero_max_age() -> Result<()> {
         min_price: 0.1,
         max_price: 1.0,
         max_age_secs: 0,
+        ..TransferGenConfig::default()
     };
 
-    let generator = DefaultTransferGenerator::new(config);
+    let generator = DefaultTransferGenerator::new(config)?;
     let transfers = generator.generate(5)?;
 
     let now = SystemTime::now()
@@ -145,3 +148,51 @@ fn test_zero_max_age() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_seeded_generation_is_reproducible() -> Result<()> {
+    let config = TransferGenConfig {
+        seed: Some(42),
+        now: Some(1_750_000_000),
+        ..TransferGenConfig::default()
+    };
+
+    let first = DefaultTransferGenerator::new(config.clone())?.generate(50)?;
+    let second = DefaultTransferGenerator::new(config)?.generate(50)?;
+
+    assert_eq!(serde_json::to_vec(&first)?, serde_json::to_vec(&second)?);
+    Ok(())
+}
+
+#[test]
+fn test_different_seeds_differ() -> Result<()> {
+    let config = |seed| TransferGenConfig {
+        seed: Some(seed),
+        now: Some(1_750_000_000),
+        ..TransferGenConfig::default()
+    };
+
+    let first = DefaultTransferGenerator::new(config(1))?.generate(10)?;
+    let second = DefaultTransferGenerator::new(config(2))?.generate(10)?;
+
+    assert_ne!(first[0].from, second[0].from);
+    Ok(())
+}
+
+#[test]
+fn test_fixed_now_anchor() -> Result<()> {
+    let now = 1_700_000_000;
+    let config = TransferGenConfig {
+        max_age_secs: 3600,
+        now: Some(now),
+        ..TransferGenConfig::default()
+    };
+
+    let transfers = DefaultTransferGenerator::new(config)?.generate(100)?;
+
+    for transfer in &transfers {
+        assert!(transfer.ts <= now);
+        assert!(transfer.ts > now - 3600);
+    }
+    Ok(())
+}
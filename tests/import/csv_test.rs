@@ -0,0 +1,184 @@
+use anyhow::Result;
+
+use mycrate::import::{read_csv, ColumnMapping, ImportError, ImportOptions, TimestampFormat};
+use mycrate::model::Amount;
+use mycrate::pipeline::calculate_user_stats;
+
+const A: &str = "0x000000000000000000000000000000000000000a";
+const B: &str = "0x000000000000000000000000000000000000000b";
+
+fn etherscan_export() -> String {
+    let alice = "0x00000000000000000000000000000000000a11ce";
+    let bob = "0x0000000000000000000000000000000000000b0b";
+    let carol = "0x00000000000000000000000000000000000ca401";
+    format!(
+        "\
+Txhash,UnixTimestamp,DateTime,From,To,Quantity,Price
+0xaa,1700000000,2023-11-14 22:13:20,{alice},{bob},10.5,1.25
+0xbb,1700000060,2023-11-14 22:14:20,{bob},{carol},4,1.30
+"
+    )
+}
+
+fn etherscan_columns() -> ColumnMapping {
+    ColumnMapping {
+        ts: "UnixTimestamp".to_string(),
+        from: "From".to_string(),
+        to: "To".to_string(),
+        amount: "Quantity".to_string(),
+        usd_price: "Price".to_string(),
+    }
+}
+
+#[test]
+fn test_default_columns() -> Result<()> {
+    let input = format!("ts,from,to,amount,usd_price\n1700000000,{A},{B},1.5,2\n");
+    let report = read_csv(input.as_bytes(), &ImportOptions::default())?;
+
+    assert!(report.errors.is_empty());
+    assert_eq!(report.transfers.len(), 1);
+    let t = &report.transfers[0];
+    assert_eq!((t.ts, t.from, t.to), (1_700_000_000, A.parse()?, B.parse()?));
+    assert_eq!((t.amount.to_string(), t.usd_price), ("1.5".to_string(), 2.0));
+    Ok(())
+}
+
+#[test]
+fn test_column_mapping() -> Result<()> {
+    let options = ImportOptions {
+        columns: etherscan_columns(),
+        ..ImportOptions::default()
+    };
+    let report = read_csv(etherscan_export().as_bytes(), &options)?;
+
+    assert!(report.errors.is_empty());
+    assert_eq!(report.transfers.len(), 2);
+    assert_eq!(report.transfers[1].from, "0x0000000000000000000000000000000000000b0b".parse()?);
+    assert_eq!(report.transfers[1].amount, Amount::from_units(4));
+
+    let stats = calculate_user_stats(&report.transfers)?;
+    assert_eq!(stats.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_iso_timestamps() -> Result<()> {
+    let options = ImportOptions {
+        columns: ColumnMapping {
+            ts: "DateTime".to_string(),
+            ..etherscan_columns()
+        },
+        timestamp: TimestampFormat::Iso8601,
+        ..ImportOptions::default()
+    };
+    let report = read_csv(etherscan_export().as_bytes(), &options)?;
+
+    let ts: Vec<u64> = report.transfers.iter().map(|t| t.ts).collect();
+    assert_eq!(ts, [1_700_000_000, 1_700_000_060]);
+    Ok(())
+}
+
+#[test]
+fn test_timestamp_formats() -> Result<()> {
+    let input = format!("\
+ts,from,to,amount,usd_price
+1700000000,{A},{B},1,1
+1700000000123,{A},{B},1,1
+2023-11-14T22:13:20Z,{A},{B},1,1
+2023-11-15T00:13:20+02:00,{A},{B},1,1
+");
+    let report = read_csv(input.as_bytes(), &ImportOptions::default())?;
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(report.transfers.iter().all(|t| t.ts == 1_700_000_000));
+
+    let millis = ImportOptions {
+        timestamp: TimestampFormat::Millis,
+        ..ImportOptions::default()
+    };
+    let input = format!("ts,from,to,amount,usd_price\n1700000000999,{A},{B},1,1\n");
+    assert_eq!(read_csv(input.as_bytes(), &millis)?.transfers[0].ts, 1_700_000_000);
+
+    let seconds = ImportOptions {
+        timestamp: TimestampFormat::Seconds,
+        ..ImportOptions::default()
+    };
+    let input = format!("ts,from,to,amount,usd_price\n2023-11-14T22:13:20Z,{A},{B},1,1\n");
+    assert_eq!(read_csv(input.as_bytes(), &seconds)?.errors.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_row_errors_are_reported() -> Result<()> {
+    let input = format!("\
+ts,from,to,amount,usd_price
+1700000000,{A},{B},1,1
+yesterday,{A},{B},1,1
+1700000001,{A},{B},lots,1
+1700000002,{A},,1,1
+1700000003,{A},{B},-5,1
+1700000004,{A},{B}
+1700000005,{A},{B},2,1
+1700000006,0xnope,{B},1,1
+");
+    let report = read_csv(input.as_bytes(), &ImportOptions::default())?;
+
+    assert_eq!(report.transfers.len(), 2);
+    let rejected: Vec<(u64, Option<&str>)> = report
+        .errors
+        .iter()
+        .map(|e| (e.line, e.column.as_deref()))
+        .collect();
+    assert_eq!(
+        rejected,
+        [
+            (3, Some("ts")),
+            (4, Some("amount")),
+            (5, Some("to")),
+            (6, Some("amount")),
+            (7, Some("amount")),
+            (9, Some("from")),
+        ]
+    );
+    assert!(report.errors[0].to_string().starts_with("line 3, column 'ts'"));
+    Ok(())
+}
+
+#[test]
+fn test_max_errors() {
+    let input = format!("ts,from,to,amount,usd_price\nx,{A},{B},1,1\ny,{A},{B},1,1\n");
+    let options = ImportOptions {
+        max_errors: Some(1),
+        ..ImportOptions::default()
+    };
+
+    match read_csv(input.as_bytes(), &options) {
+        Err(ImportError::TooManyErrors { limit, errors }) => {
+            assert_eq!(limit, 1);
+            assert_eq!(errors.len(), 2);
+        }
+        other => panic!("expected TooManyErrors, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_missing_column() {
+    let input = format!("ts,from,to,value,usd_price\n1,{A},{B},1,1\n");
+
+    assert!(matches!(
+        read_csv(input.as_bytes(), &ImportOptions::default()),
+        Err(ImportError::MissingColumn(column)) if column == "amount"
+    ));
+}
+
+#[test]
+fn test_custom_delimiter() -> Result<()> {
+    let input = format!("ts;from;to;amount;usd_price\n1700000000; {A} ;{B};1.5;2\n");
+    let options = ImportOptions {
+        delimiter: b';',
+        ..ImportOptions::default()
+    };
+    let report = read_csv(input.as_bytes(), &options)?;
+
+    assert_eq!(report.transfers[0].from, A.parse()?);
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use mycrate::import::erc20::{
+    decode_logs, parse_block_timestamps, parse_logs, Erc20Config, FixedPrice, PriceTable,
+};
+use mycrate::import::ImportError;
+use mycrate::model::Amount;
+
+const LOGS: &str = include_str!("../fixtures/erc20/logs.json");
+const BLOCKS: &str = include_str!("../fixtures/erc20/blocks.json");
+
+const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+
+fn usdc() -> Result<Erc20Config> {
+    Ok(Erc20Config {
+        decimals: 6,
+        token: Some(USDC.parse()?),
+        ..Erc20Config::default()
+    })
+}
+
+#[test]
+fn test_decode_fixture_logs() -> Result<()> {
+    let logs = parse_logs(LOGS)?;
+    let blocks = parse_block_timestamps(BLOCKS)?;
+    assert_eq!(logs.len(), 8);
+    assert_eq!(blocks.get(&19_000_000), Some(&1_705_554_624));
+
+    let report = decode_logs(&logs, &usdc()?, &blocks, &FixedPrice(1.0))?;
+
+    let decoded: Vec<_> = report
+        .transfers
+        .iter()
+        .map(|t| (t.ts, t.from.to_string(), t.to.to_string(), t.amount.to_string()))
+        .collect();
+    assert_eq!(
+        decoded,
+        [
+            (
+                1_705_554_624,
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+                "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359".to_string(),
+                "1500".to_string()
+            ),
+            (
+                1_705_554_624,
+                "0x0000000000000000000000000000000000000000".to_string(),
+                "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB".to_string(),
+                "2.5".to_string()
+            ),
+            (
+                1_705_554_660,
+                "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB".to_string(),
+                "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb".to_string(),
+                "0.000001".to_string()
+            ),
+        ]
+    );
+    assert!(report.transfers.iter().all(|t| t.usd_price == 1.0));
+
+    let errors: Vec<_> = report
+        .errors
+        .iter()
+        .map(|e| (e.line, e.column.as_deref()))
+        .collect();
+    assert_eq!(errors, [(4, Some("topics")), (7, Some("blockNumber"))]);
+    Ok(())
+}
+
+#[test]
+fn test_without_token_filter() -> Result<()> {
+    let logs = parse_logs(LOGS)?;
+    let blocks = parse_block_timestamps(BLOCKS)?;
+
+    let report = decode_logs(&logs, &Erc20Config::default(), &blocks, &FixedPrice(1.0))?;
+
+    // The DAI log has 18 decimals, the USDC ones are off by 10^12.
+    assert_eq!(report.transfers.len(), 4);
+    assert_eq!(report.transfers[3].amount, Amount::from_units(3));
+    assert_eq!(report.transfers[0].amount.to_string(), "0.0000000015");
+    Ok(())
+}
+
+#[test]
+fn test_price_table_lookup() -> Result<()> {
+    let logs = parse_logs(LOGS)?;
+    let blocks = parse_block_timestamps(BLOCKS)?;
+    let prices = PriceTable::new(vec![(1_705_554_650, 1.01), (1_705_554_600, 0.99)]);
+
+    let report = decode_logs(&logs, &usdc()?, &blocks, &prices)?;
+
+    let prices: Vec<_> = report.transfers.iter().map(|t| t.usd_price).collect();
+    assert_eq!(prices, [0.99, 0.99, 1.01]);
+
+    let late = PriceTable::new(vec![(1_705_554_650, 1.0)]);
+    let report = decode_logs(&logs, &usdc()?, &blocks, &late)?;
+    assert_eq!(report.transfers.len(), 1);
+    assert!(report
+        .errors
+        .iter()
+        .any(|e| e.line == 1 && e.column.as_deref() == Some("usd_price")));
+    Ok(())
+}
+
+#[test]
+fn test_bare_log_array_and_malformed_data() -> Result<()> {
+    let logs = parse_logs(
+        r#"[{
+            "address": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+            "topics": [
+                "0xDDF252AD1BE2C89B69C2B068FC378DAA952BA7F163C4A11628F55A4DF523B3EF",
+                "0x0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+                "0x100000000000000000000000fb6916095ca1df60bb79ce92ce3ea74c37c5d359"
+            ],
+            "data": "0x01",
+            "blockNumber": "0x1"
+        }]"#,
+    )?;
+    let timestamps = HashMap::from([(1, 1_000)]);
+
+    let report = decode_logs(&logs, &usdc()?, &timestamps, &FixedPrice(1.0))?;
+
+    assert!(report.transfers.is_empty());
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].message.contains("not a padded address"));
+    assert!(parse_logs(r#"{"jsonrpc": "2.0", "id": 1}"#).is_err());
+
+    let strict = Erc20Config {
+        max_errors: Some(0),
+        ..usdc()?
+    };
+    assert!(matches!(
+        decode_logs(&logs, &strict, &timestamps, &FixedPrice(1.0)),
+        Err(ImportError::TooManyErrors { limit: 0, .. })
+    ));
+    Ok(())
+}
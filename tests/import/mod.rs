@@ -0,0 +1,8 @@
+#[cfg(test)]
+pub mod csv_test;
+
+#[cfg(test)]
+pub mod ndjson_test;
+
+#[cfg(test)]
+pub mod erc20_test;
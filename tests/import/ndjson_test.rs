@@ -0,0 +1,104 @@
+use anyhow::Result;
+
+use mycrate::generator::generate_transfers;
+use mycrate::import::{read_ndjson, ColumnMapping, ImportOptions, TimestampFormat};
+
+/// Expands the `0xa`, `0xb` and `0xc` placeholders into full addresses.
+fn with_addresses(input: &str) -> String {
+    ["a", "b", "c"].iter().fold(input.to_string(), |input, name| {
+        input.replace(&format!("\"0x{}\"", name), &format!("\"0x{:0>40}\"", name))
+    })
+}
+
+#[test]
+fn test_round_trip_generated() -> Result<()> {
+    let transfers = generate_transfers(50)?;
+    let input = transfers
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n");
+
+    let report = read_ndjson(input.as_bytes(), &ImportOptions::default())?;
+
+    assert!(report.errors.is_empty());
+    assert_eq!(
+        serde_json::to_string(&report.transfers)?,
+        serde_json::to_string(&transfers)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_mapped_fields_and_string_numbers() -> Result<()> {
+    let input = r#"
+{"block_time": "2023-11-14T22:13:20Z", "sender": "0xa", "recipient": "0xb", "value": "12.5", "price": 0.5}
+
+{"block_time": "2023-11-14 22:14:20", "sender": "0xb", "recipient": "0xc", "value": 3, "price": "0.75"}
+"#;
+    let options = ImportOptions {
+        columns: ColumnMapping {
+            ts: "block_time".to_string(),
+            from: "sender".to_string(),
+            to: "recipient".to_string(),
+            amount: "value".to_string(),
+            usd_price: "price".to_string(),
+        },
+        timestamp: TimestampFormat::Iso8601,
+        ..ImportOptions::default()
+    };
+    let report = read_ndjson(with_addresses(input).as_bytes(), &options)?;
+
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    let fields: Vec<(u64, String, f64)> = report
+        .transfers
+        .iter()
+        .map(|t| (t.ts, t.amount.to_string(), t.usd_price))
+        .collect();
+    assert_eq!(
+        fields,
+        [(1_700_000_000, "12.5".to_string(), 0.5), (1_700_000_060, "3".to_string(), 0.75)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_millisecond_numbers() -> Result<()> {
+    let input = r#"{"ts": 1700000000500, "from": "0xa", "to": "0xb", "amount": 1, "usd_price": 1}"#;
+    let report = read_ndjson(with_addresses(input).as_bytes(), &ImportOptions::default())?;
+
+    assert_eq!(report.transfers[0].ts, 1_700_000_000);
+    Ok(())
+}
+
+#[test]
+fn test_row_errors_are_reported() -> Result<()> {
+    let input = r#"{"ts": 1, "from": "0xa", "to": "0xb", "amount": 1, "usd_price": 1}
+not json
+[1, 2, 3]
+{"ts": -1, "from": "0xa", "to": "0xb", "amount": 1, "usd_price": 1}
+{"ts": 1, "from": 42, "to": "0xb", "amount": 1, "usd_price": 1}
+{"ts": 1, "from": "0xa", "to": "0xb", "amount": 1}
+{"ts": 1, "from": "0xa", "to": "0xnope", "amount": 1, "usd_price": 1}
+"#;
+    let report = read_ndjson(with_addresses(input).as_bytes(), &ImportOptions::default())?;
+
+    assert_eq!(report.transfers.len(), 1);
+    let rejected: Vec<(u64, Option<&str>)> = report
+        .errors
+        .iter()
+        .map(|e| (e.line, e.column.as_deref()))
+        .collect();
+    assert_eq!(
+        rejected,
+        [
+            (2, None),
+            (3, None),
+            (4, Some("ts")),
+            (5, Some("from")),
+            (6, Some("usd_price")),
+            (7, Some("to")),
+        ]
+    );
+    Ok(())
+}
@@ -1,2 +1,7 @@
+pub mod cli;
+pub mod export;
 pub mod generator;
+pub mod import;
+pub mod model;
 pub mod pipeline;
+pub mod storage;
@@ -0,0 +1,70 @@
+use anyhow::Result;
+
+use mycrate::model::{Address, AddressError, Transfer};
+
+use super::addr;
+
+// EIP-55 test vectors.
+const CHECKSUMMED: [&str; 4] = [
+    "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+    "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+    "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+    "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+];
+
+#[test]
+fn test_checksum_display() -> Result<()> {
+    for expected in CHECKSUMMED {
+        let address: Address = expected.to_lowercase().parse()?;
+        assert_eq!(address.to_string(), expected);
+    }
+    assert_eq!(Address::ZERO.to_string(), "0x0000000000000000000000000000000000000000");
+    Ok(())
+}
+
+#[test]
+fn test_parse_is_case_insensitive() -> Result<()> {
+    let checksummed: Address = CHECKSUMMED[0].parse()?;
+
+    for input in [
+        "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+        "0X5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+        "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+    ] {
+        assert_eq!(input.parse::<Address>()?, checksummed);
+    }
+    assert_eq!(checksummed.as_bytes()[0], 0x5a);
+    Ok(())
+}
+
+#[test]
+fn test_parse_errors() {
+    for input in ["", "0x", "0x5aaeb6", "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed00"] {
+        assert!(matches!(input.parse::<Address>(), Err(AddressError::Length(_))), "{input}");
+    }
+    assert!(matches!(
+        "0xzaaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse::<Address>(),
+        Err(AddressError::Hex(_))
+    ));
+}
+
+#[test]
+fn test_json_round_trip() -> Result<()> {
+    let transfer = Transfer {
+        ts: 1,
+        from: CHECKSUMMED[1].parse()?,
+        to: addr("b"),
+        amount: "1".parse()?,
+        usd_price: 1.0,
+    };
+
+    let json = serde_json::to_string(&transfer)?;
+    assert!(json.contains(&format!(r#""from":"{}""#, CHECKSUMMED[1])));
+    let parsed: Transfer = serde_json::from_str(&json)?;
+    assert_eq!((parsed.from, parsed.to), (transfer.from, transfer.to));
+
+    let lowercase = json.replace(CHECKSUMMED[1], &CHECKSUMMED[1].to_lowercase());
+    assert_eq!(serde_json::from_str::<Transfer>(&lowercase)?.from, transfer.from);
+    assert!(serde_json::from_str::<Address>(r#""0xnope""#).is_err());
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+
+use mycrate::model::{Amount, AmountError, Transfer};
+
+use super::addr;
+
+#[test]
+fn test_parse_and_display() -> Result<()> {
+    for (input, display) in [
+        ("12.5", "12.5"),
+        ("3", "3"),
+        ("3.000", "3"),
+        ("+0.1", "0.1"),
+        (".5", "0.5"),
+        ("-0.000001", "-0.000001"),
+        ("0.000000000000000001", "0.000000000000000001"),
+    ] {
+        assert_eq!(input.parse::<Amount>()?.to_string(), display);
+    }
+
+    assert!(matches!("".parse::<Amount>(), Err(AmountError::Invalid(_))));
+    assert!(matches!("1e3".parse::<Amount>(), Err(AmountError::Invalid(_))));
+    assert!(matches!("1.-5".parse::<Amount>(), Err(AmountError::Invalid(_))));
+    assert!(matches!(
+        "0.0000000000000000001".parse::<Amount>(),
+        Err(AmountError::TooPrecise(_))
+    ));
+    assert!(matches!(
+        "1000000000000000000000".parse::<Amount>(),
+        Err(AmountError::Overflow(_))
+    ));
+    assert!(matches!(
+        "-100000000000000000000".parse::<Amount>(),
+        Err(AmountError::Overflow(_))
+    ));
+    assert_eq!("99999999999999999999.999999999999999999".parse::<Amount>()?, Amount::MAX);
+    Ok(())
+}
+
+#[test]
+fn test_sums_are_exact() -> Result<()> {
+    let tenth: Amount = "0.1".parse()?;
+    let sum: Amount = std::iter::repeat_n(tenth, 1_000_000).sum();
+
+    assert_eq!(sum, Amount::from_units(100_000));
+    assert_ne!((0..1_000_000).map(|_| 0.1).sum::<f64>(), 100_000.0);
+    Ok(())
+}
+
+#[test]
+fn test_f64_conversions() {
+    assert_eq!(Amount::from_f64(0.1).map(|a| a.raw()), Some(10i128.pow(17)));
+    assert_eq!(Amount::from_f64(-2.5).map(|a| a.to_string()), Some("-2.5".to_string()));
+    assert_eq!(Amount::from_f64(1e-20), Some(Amount::ZERO));
+    assert_eq!(Amount::from_f64(f64::NAN), None);
+    assert_eq!(Amount::from_f64(1e30), None);
+    assert_eq!(Amount::from_units(7).to_f64(), 7.0);
+}
+
+#[test]
+fn test_token_values() {
+    assert_eq!(Amount::from_token_value(1_500_000, 6), Some(Amount::from_raw(15 * 10i128.pow(17))));
+    assert_eq!(Amount::from_token_value(10u128.pow(18), 18), Some(Amount::from_units(1)));
+    assert_eq!(Amount::from_token_value(123, 20).map(|a| a.raw()), Some(1));
+    assert_eq!(Amount::from_token_value(u128::MAX, 18), None);
+    assert_eq!(Amount::from_token_value(10u128.pow(38), 18), None);
+    assert!(Amount::from_token_value(10u128.pow(38) - 1, 18).is_some());
+}
+
+#[test]
+fn test_checked_arithmetic() {
+    let one = Amount::from_units(1);
+
+    assert_eq!(Amount::MAX.checked_add(one), None);
+    assert_eq!(Amount::MIN.checked_sub(one), None);
+    assert_eq!(Amount::MAX.raw(), 10i128.pow(38) - 1);
+    assert_eq!(Amount::MAX.saturating_add(one), Amount::MAX);
+    assert_eq!(one.checked_sub(Amount::from_units(3)), Some(Amount::from_units(-2)));
+    assert!((-one).is_negative());
+}
+
+#[test]
+fn test_json_round_trip() -> Result<()> {
+    let transfer = Transfer {
+        ts: 1,
+        from: addr("a"),
+        to: addr("b"),
+        amount: "123456789.123456789123456789".parse()?,
+        usd_price: 1.0,
+    };
+
+    let json = serde_json::to_string(&transfer)?;
+    assert!(json.contains(r#""amount":"123456789.123456789123456789""#));
+    let parsed: Transfer = serde_json::from_str(&json)?;
+    assert_eq!(parsed.amount, transfer.amount);
+
+    let numeric: Transfer =
+        serde_json::from_str(&json.replace(r#""123456789.123456789123456789""#, "2.25"))?;
+    assert_eq!(numeric.amount.to_string(), "2.25");
+    Ok(())
+}
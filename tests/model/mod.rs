@@ -0,0 +1,14 @@
+#[cfg(test)]
+pub mod address_test;
+
+#[cfg(test)]
+pub mod amount_test;
+
+/// Readable test address: `name` right-aligned in zero bytes, e.g. `addr("A")`
+/// is `0x0000000000000000000000000000000000000041`.
+#[cfg(test)]
+pub(crate) fn addr(name: &str) -> mycrate::model::Address {
+    let mut bytes = [0; 20];
+    bytes[20 - name.len()..].copy_from_slice(name.as_bytes());
+    mycrate::model::Address::from_bytes(bytes)
+}
@@ -0,0 +1,345 @@
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, Result};
+    use rand::Rng;
+    use std::collections::HashMap;
+    use mycrate::model::{Address, Amount, Transfer, UserStats};
+    use mycrate::pipeline::{
+        calculate_user_stats, calculate_user_stats_with, window_max, BalanceWindow, LatePolicy,
+        PipelineConfig, StatsEngine,
+    };
+
+    use crate::model::addr;
+
+    const BASE_TS: u64 = 1_748_174_400;
+    const HOUR: u64 = 3_600;
+    const DAY: u64 = 86_400;
+
+    fn transfer(from: &str, to: &str, amount: i64, ts: u64, usd_price: f64) -> Transfer {
+        Transfer {
+            ts,
+            from: addr(from),
+            to: addr(to),
+            amount: Amount::from_units(amount),
+            usd_price,
+        }
+    }
+
+    fn find<'a>(stats: &'a [UserStats], address: &str) -> Result<&'a UserStats> {
+        stats
+            .iter()
+            .find(|s| s.address == addr(address))
+            .with_context(|| format!("{} stats not found", address))
+    }
+
+    fn window(stats: &UserStats, label: &str) -> Result<Amount> {
+        stats
+            .max_balance_windows
+            .get(label)
+            .copied()
+            .with_context(|| format!("{} window not found for {}", label, stats.address))
+    }
+
+    fn with_policy(late_policy: LatePolicy) -> PipelineConfig {
+        PipelineConfig {
+            late_policy,
+            ..PipelineConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_incremental_matches_batch() -> Result<()> {
+        let transfers = vec![
+            transfer("A", "B", 10, BASE_TS, 1.0),
+            transfer("B", "C", 4, BASE_TS + HOUR, 2.0),
+            transfer("C", "A", 1, BASE_TS + 2 * HOUR, 3.0),
+            transfer("A", "C", 2, BASE_TS + DAY, 4.0),
+        ];
+
+        let mut engine = StatsEngine::default();
+        engine.push_batch(&transfers[..2])?;
+        for t in &transfers[2..] {
+            engine.push(t)?;
+        }
+
+        let incremental = engine.snapshot()?;
+        let batch = calculate_user_stats(&transfers)?;
+
+        assert_eq!(incremental.len(), batch.len());
+        for (a, b) in incremental.iter().zip(&batch) {
+            assert_eq!(a.address, b.address);
+            assert_eq!(a.total_volume, b.total_volume);
+            assert_eq!(a.avg_buy_price, b.avg_buy_price);
+            assert_eq!(a.avg_sell_price, b.avg_sell_price);
+            assert_eq!(a.max_balance, b.max_balance);
+            assert_eq!(a.max_balance_windows, b.max_balance_windows);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_between_pushes() -> Result<()> {
+        let mut engine = StatsEngine::default();
+
+        engine.push(&transfer("A", "B", 10, BASE_TS, 2.0))?;
+        assert_eq!(find(&engine.snapshot()?, "B")?.total_volume, Amount::from_units(10));
+
+        engine.push(&transfer("C", "B", 30, BASE_TS + 60, 4.0))?;
+        let b = engine.snapshot()?;
+        let b = find(&b, "B")?;
+        assert_eq!(b.total_volume, Amount::from_units(40));
+        assert_eq!(b.avg_buy_price, 3.5);
+        assert_eq!(b.max_balance, Amount::from_units(40));
+        Ok(())
+    }
+
+    #[test]
+    fn test_volume_is_exact() -> Result<()> {
+        let tenth = "0.1".parse::<Amount>()?;
+        let mut engine = StatsEngine::default();
+
+        for i in 0..1_000 {
+            let mut t = transfer("A", "B", 0, BASE_TS + i, 1.0);
+            t.amount = tenth;
+            engine.push(&t)?;
+        }
+
+        let stats = engine.snapshot()?;
+        assert_eq!(find(&stats, "B")?.total_volume, Amount::from_units(100));
+        assert_eq!(find(&stats, "A")?.max_balance, Amount::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn test_windows_trail_the_watermark() -> Result<()> {
+        let mut engine = StatsEngine::default();
+
+        engine.push(&transfer("M", "A", 100, BASE_TS, 1.0))?;
+        engine.push(&transfer("A", "M", 90, BASE_TS + HOUR, 1.0))?;
+        engine.push(&transfer("M", "A", 5, BASE_TS + 2 * DAY, 1.0))?;
+        engine.push(&transfer("A", "M", 10, BASE_TS + 2 * DAY + 2 * HOUR, 1.0))?;
+        engine.push(&transfer("M", "B", 1, BASE_TS + 2 * DAY + 4 * HOUR, 1.0))?;
+
+        let stats = engine.snapshot()?;
+        let a = find(&stats, "A")?;
+
+        assert_eq!(a.max_balance, Amount::from_units(100));
+        assert_eq!(window(a, "7d")?, Amount::from_units(100));
+        assert_eq!(window(a, "24h")?, Amount::from_units(15));
+        assert_eq!(window(a, "1h")?, Amount::from_units(5));
+        assert_eq!(window(find(&stats, "M")?, "1h")?, Amount::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quiet_address_reports_carried_balance() -> Result<()> {
+        let mut engine = StatsEngine::default();
+
+        engine.push(&transfer("M", "A", 50, BASE_TS, 1.0))?;
+        engine.push(&transfer("A", "M", 20, BASE_TS + HOUR, 1.0))?;
+        engine.push(&transfer("M", "B", 1, BASE_TS + 30 * DAY, 1.0))?;
+
+        let stats = engine.snapshot()?;
+        let a = find(&stats, "A")?;
+
+        assert_eq!(a.max_balance, Amount::from_units(50));
+        assert_eq!(window(a, "1h")?, Amount::from_units(30));
+        assert_eq!(window(a, "7d")?, Amount::from_units(30));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow_leaves_engine_untouched() -> Result<()> {
+        let mut engine = StatsEngine::default();
+
+        let mut whale = transfer("M", "B", 0, BASE_TS, 1.0);
+        whale.amount = Amount::MAX;
+        engine.push(&whale)?;
+
+        let mut overflow = transfer("A", "B", 0, BASE_TS + 10, 1.0);
+        overflow.amount = Amount::from_raw(1);
+        assert!(engine.push(&overflow).is_err());
+
+        let stats = engine.snapshot()?;
+        assert!(find(&stats, "A").is_err(), "sender was debited");
+        assert_eq!(find(&stats, "B")?.total_volume, Amount::MAX);
+        assert_eq!(engine.watermark(), Some(BASE_TS));
+        Ok(())
+    }
+
+    #[test]
+    fn test_late_transfer_rejected() -> Result<()> {
+        let mut engine = StatsEngine::new(with_policy(LatePolicy::Reject));
+
+        engine.push(&transfer("A", "B", 1, BASE_TS + 10, 1.0))?;
+        assert!(engine.push(&transfer("A", "B", 1, BASE_TS, 1.0)).is_err());
+
+        assert_eq!(engine.watermark(), Some(BASE_TS + 10));
+        assert_eq!(find(&engine.snapshot()?, "A")?.total_volume, Amount::from_units(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_late_transfer_skipped() -> Result<()> {
+        let mut engine = StatsEngine::new(with_policy(LatePolicy::Skip));
+
+        engine.push(&transfer("A", "B", 1, BASE_TS + 10, 1.0))?;
+        engine.push(&transfer("A", "B", 1, BASE_TS, 1.0))?;
+
+        assert_eq!(engine.skipped(), 1);
+        assert_eq!(find(&engine.snapshot()?, "A")?.total_volume, Amount::from_units(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_late_transfer_clamped() -> Result<()> {
+        let mut engine = StatsEngine::new(with_policy(LatePolicy::Clamp));
+
+        engine.push(&transfer("A", "B", 1, BASE_TS + 10, 1.0))?;
+        engine.push(&transfer("A", "B", 2, BASE_TS, 1.0))?;
+
+        assert_eq!(engine.watermark(), Some(BASE_TS + 10));
+        assert_eq!(find(&engine.snapshot()?, "B")?.max_balance, Amount::from_units(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_is_ordered_before_apply() -> Result<()> {
+        let mut engine = StatsEngine::new(with_policy(LatePolicy::Reject));
+
+        engine.push_batch(&[
+            transfer("B", "A", 5, BASE_TS + HOUR, 1.0),
+            transfer("M", "B", 5, BASE_TS, 1.0),
+        ])?;
+
+        let stats = engine.snapshot()?;
+        assert_eq!(find(&stats, "B")?.max_balance, Amount::from_units(5));
+        assert_eq!(engine.watermark(), Some(BASE_TS + HOUR));
+        Ok(())
+    }
+
+    /// The balance held at the start of the window ending at `end` and every
+    /// balance set after it, or zero.
+    fn brute_force_window_max(history: &[(u64, Amount)], window: u64, end: u64) -> Amount {
+        let cutoff = end.saturating_sub(window);
+        let carried = history.iter().rev().find(|&&(ts, _)| ts <= cutoff);
+        history
+            .iter()
+            .filter(|&&(ts, _)| ts > cutoff)
+            .chain(carried)
+            .map(|&(_, balance)| balance)
+            .fold(Amount::ZERO, Amount::max)
+    }
+
+    #[test]
+    fn test_windows_match_brute_force() -> Result<()> {
+        let mut rng = rand::rng();
+        let addresses = ["A", "B", "C", "D", "E"];
+
+        let mut ts = BASE_TS;
+        let mut transfers = Vec::new();
+        for _ in 0..2_000 {
+            ts += rng.random_range(0..2 * HOUR);
+            let from = addresses[rng.random_range(0..addresses.len())];
+            let to = addresses[rng.random_range(0..addresses.len())];
+            let mut t = transfer(from, to, 0, ts, 1.0);
+            t.amount = Amount::from_raw(rng.random_range(10i128.pow(18)..10i128.pow(20)));
+            transfers.push(t);
+        }
+
+        let mut balances: HashMap<Address, Amount> = HashMap::new();
+        let mut history: HashMap<Address, Vec<(u64, Amount)>> = HashMap::new();
+        for t in &transfers {
+            for (address, delta) in [(t.from, -t.amount), (t.to, t.amount)] {
+                let balance = balances.entry(address).or_default();
+                *balance += delta;
+                history.entry(address).or_default().push((t.ts, *balance));
+            }
+        }
+
+        let stats = calculate_user_stats(&transfers)?;
+        for (address, h) in &history {
+            let end = ts;
+            let s = stats
+                .iter()
+                .find(|s| s.address == *address)
+                .with_context(|| format!("{} stats not found", address))?;
+            assert_eq!(window(s, "1h")?, brute_force_window_max(h, HOUR, end));
+            assert_eq!(window(s, "24h")?, brute_force_window_max(h, DAY, end));
+            assert_eq!(window(s, "7d")?, brute_force_window_max(h, 7 * DAY, end));
+            assert_eq!(window_max(h, HOUR, end), brute_force_window_max(h, HOUR, end));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_windows() -> Result<()> {
+        let config = PipelineConfig {
+            windows: vec!["5m".parse()?, "30d".parse()?, "90d".parse()?],
+            ..PipelineConfig::default()
+        };
+
+        let transfers = vec![
+            transfer("M", "A", 70, BASE_TS, 1.0),
+            transfer("A", "M", 60, BASE_TS + 10 * DAY, 1.0),
+            transfer("M", "A", 1, BASE_TS + 40 * DAY + HOUR, 1.0),
+        ];
+        let stats = calculate_user_stats_with(&transfers, &config)?;
+        let a = find(&stats, "A")?;
+
+        let labels: Vec<_> = a.max_balance_windows.keys().map(String::as_str).collect();
+        assert_eq!(labels, vec!["30d", "5m", "90d"]);
+        assert_eq!(window(a, "5m")?, Amount::from_units(11));
+        assert_eq!(window(a, "30d")?, Amount::from_units(11));
+        assert_eq!(window(a, "90d")?, Amount::from_units(70));
+        Ok(())
+    }
+
+    #[test]
+    fn test_window_parsing() -> Result<()> {
+        let cases = [("30s", 30), ("5m", 300), ("24h", 86_400), ("7d", 604_800), ("2w", 1_209_600)];
+        for (label, secs) in cases {
+            let window: BalanceWindow = label.parse()?;
+            assert_eq!(window.secs(), secs);
+            assert_eq!(window.to_string(), label);
+        }
+
+        for invalid in ["", "h", "10", "0h", "5y", "-1h"] {
+            assert!(invalid.parse::<BalanceWindow>().is_err(), "{} should be rejected", invalid);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_windows_rejected() -> Result<()> {
+        let transfers = vec![transfer("A", "B", 1, BASE_TS, 1.0)];
+
+        for windows in [["1h", "1h"], ["1h", "60m"]] {
+            let config = PipelineConfig {
+                windows: vec![windows[0].parse()?, windows[1].parse()?],
+                ..PipelineConfig::default()
+            };
+            assert!(config.validate().is_err(), "{:?} should be rejected", windows);
+            assert!(calculate_user_stats_with(&transfers, &config).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_deserialization() -> Result<()> {
+        let config: PipelineConfig = toml::from_str(
+            r#"
+            windows = ["5m", "90d"]
+            late_policy = "clamp"
+            "#,
+        )?;
+
+        let labels: Vec<&str> = config.windows.iter().map(BalanceWindow::label).collect();
+        assert_eq!(labels, ["5m", "90d"]);
+        assert_eq!(config.late_policy, LatePolicy::Clamp);
+        assert_eq!(config.threads, 1);
+
+        assert!(toml::from_str::<PipelineConfig>(r#"windows = ["5y"]"#).is_err());
+        Ok(())
+    }
+}
@@ -1,2 +1,8 @@
+#[cfg(test)]
+pub mod engine_test;
+
+#[cfg(test)]
+pub mod parallel_test;
+
 #[cfg(test)]
 pub mod pipeline_test;
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use mycrate::generator::generate_transfers;
+    use mycrate::pipeline::{calculate_user_stats, calculate_user_stats_with, PipelineConfig};
+
+    fn with_threads(threads: usize) -> PipelineConfig {
+        PipelineConfig {
+            threads,
+            ..PipelineConfig::default()
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() -> Result<()> {
+        let mut transfers = generate_transfers(2_000)?;
+        for i in (0..transfers.len()).step_by(3) {
+            transfers[i].to = transfers[0].from;
+        }
+
+        let sequential = calculate_user_stats(&transfers)?;
+        for threads in [0, 2, 4] {
+            let parallel = calculate_user_stats_with(&transfers, &with_threads(threads))?;
+
+            assert_eq!(parallel.len(), sequential.len());
+            for (p, s) in parallel.iter().zip(&sequential) {
+                assert_eq!(p.address, s.address);
+                assert_eq!(p.total_volume, s.total_volume);
+                assert_eq!(p.avg_buy_price, s.avg_buy_price);
+                assert_eq!(p.avg_sell_price, s.avg_sell_price);
+                assert_eq!(p.max_balance, s.max_balance);
+                assert_eq!(p.max_balance_windows, s.max_balance_windows);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_output_is_deterministic() -> Result<()> {
+        let transfers = generate_transfers(500)?;
+
+        let first = calculate_user_stats_with(&transfers, &with_threads(4))?;
+        let second = calculate_user_stats_with(&transfers, &with_threads(4))?;
+
+        let addresses = |stats: &[mycrate::model::UserStats]| {
+            stats.iter().map(|s| s.address).collect::<Vec<_>>()
+        };
+        assert_eq!(addresses(&first), addresses(&second));
+        assert!(first.windows(2).all(|w| w[0].address < w[1].address));
+        Ok(())
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_threads_require_feature() -> Result<()> {
+        let transfers = generate_transfers(10)?;
+
+        assert!(calculate_user_stats_with(&transfers, &with_threads(4)).is_err());
+        assert!(calculate_user_stats_with(&transfers, &with_threads(1)).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_input() -> Result<()> {
+        assert!(calculate_user_stats_with(&[], &with_threads(1))?.is_empty());
+        assert!(calculate_user_stats(&[])?.is_empty());
+        Ok(())
+    }
+}
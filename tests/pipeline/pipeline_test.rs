@@ -2,7 +2,9 @@
 mod tests {
     use chrono::{Utc, TimeZone};
     use mycrate::pipeline::calculate_user_stats;
-    use mycrate::model::{Transfer};
+    use mycrate::model::{Amount, Transfer};
+
+    use crate::model::addr;
     use anyhow::Context;
 
     #[test]
@@ -22,9 +24,9 @@ mod tests {
 
         let transfers = vec![
             Transfer {
-                from: "Alice".to_string(),
-                to: "Bob".to_string(),
-                amount: 10.0,
+                from: addr("Alice"),
+                to: addr("Bob"),
+                amount: Amount::from_units(10),
                 ts,
                 usd_price: 5.0,
             }
@@ -33,19 +35,19 @@ mod tests {
         let stats = calculate_user_stats(&transfers).context("Failed to calculate user stats")?;
 
         let alice_stats = stats.iter()
-            .find(|s| s.address == "Alice")
+            .find(|s| s.address == addr("Alice"))
             .context("Alice stats not found")?;
         let bob_stats = stats.iter()
-            .find(|s| s.address == "Bob")
+            .find(|s| s.address == addr("Bob"))
             .context("Bob stats not found")?;
 
-        assert!(alice_stats.max_balance >= 0.0);
-        assert_eq!(alice_stats.total_volume, 10.0);
+        assert!(alice_stats.max_balance >= Amount::ZERO);
+        assert_eq!(alice_stats.total_volume, Amount::from_units(10));
         assert_eq!(alice_stats.avg_sell_price, 5.0);
         assert_eq!(alice_stats.avg_buy_price, 0.0);
 
-        assert!(bob_stats.max_balance >= 0.0);
-        assert_eq!(bob_stats.total_volume, 10.0);
+        assert!(bob_stats.max_balance >= Amount::ZERO);
+        assert_eq!(bob_stats.total_volume, Amount::from_units(10));
         assert_eq!(bob_stats.avg_buy_price, 5.0);
         assert_eq!(bob_stats.avg_sell_price, 0.0);
 
@@ -61,23 +63,23 @@ mod tests {
 
         let transfers = vec![
             Transfer {
-                from: "Alice".to_string(),
-                to: "Bob".to_string(),
-                amount: 10.0,
+                from: addr("Alice"),
+                to: addr("Bob"),
+                amount: Amount::from_units(10),
                 ts: base_ts,
                 usd_price: 5.0,
             },
             Transfer {
-                from: "Bob".to_string(),
-                to: "Charlie".to_string(),
-                amount: 5.0,
+                from: addr("Bob"),
+                to: addr("Charlie"),
+                amount: Amount::from_units(5),
                 ts: base_ts + 3600,
                 usd_price: 6.0,
             },
             Transfer {
-                from: "Alice".to_string(),
-                to: "Charlie".to_string(),
-                amount: 2.0,
+                from: addr("Alice"),
+                to: addr("Charlie"),
+                amount: Amount::from_units(2),
                 ts: base_ts + 7200,
                 usd_price: 4.0,
             },
@@ -86,19 +88,19 @@ mod tests {
         let stats = calculate_user_stats(&transfers).context("Failed to calculate user stats")?;
 
         for stat in &stats {
-            assert!(stat.total_volume > 0.0);
+            assert!(stat.total_volume > Amount::ZERO);
         }
 
         let bob_stats = stats.iter()
-            .find(|s| s.address == "Bob")
+            .find(|s| s.address == addr("Bob"))
             .context("Bob stats not found")?;
         assert_eq!(bob_stats.avg_buy_price, 5.0);
         assert_eq!(bob_stats.avg_sell_price, 6.0);
 
         let charlie_stats = stats.iter()
-            .find(|s| s.address == "Charlie")
+            .find(|s| s.address == addr("Charlie"))
             .context("Charlie stats not found")?;
-        assert!(charlie_stats.max_balance >= 0.0);
+        assert!(charlie_stats.max_balance >= Amount::ZERO);
 
         Ok(())
     }
@@ -112,23 +114,23 @@ mod tests {
 
         let transfers = vec![
             Transfer {
-                from: "A".to_string(),
-                to: "B".to_string(),
-                amount: 10.0,
+                from: addr("A"),
+                to: addr("B"),
+                amount: Amount::from_units(10),
                 ts: base_ts,
                 usd_price: 1.0,
             },
             Transfer {
-                from: "B".to_string(),
-                to: "A".to_string(),
-                amount: 5.0,
+                from: addr("B"),
+                to: addr("A"),
+                amount: Amount::from_units(5),
                 ts: base_ts + 1800,
                 usd_price: 1.0,
             },
             Transfer {
-                from: "A".to_string(),
-                to: "B".to_string(),
-                amount: 3.0,
+                from: addr("A"),
+                to: addr("B"),
+                amount: Amount::from_units(3),
                 ts: base_ts + 3600,
                 usd_price: 1.0,
             },
@@ -137,10 +139,10 @@ mod tests {
         let stats = calculate_user_stats(&transfers).context("Failed to calculate user stats")?;
 
         let a_stats = stats.iter()
-            .find(|s| s.address == "A")
+            .find(|s| s.address == addr("A"))
             .context("A stats not found")?;
 
-        assert!(a_stats.max_balance >= 0.0);
+        assert!(a_stats.max_balance >= Amount::ZERO);
 
         Ok(())
     }
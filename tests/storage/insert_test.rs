@@ -0,0 +1,144 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use clickhouse::error::Error;
+use futures::future::ready;
+
+use mycrate::storage::insert::is_transient;
+use mycrate::storage::{
+    ChunkedInsert, InMemoryStorage, InsertConfig, InsertProgress, InsertSummary, Storage,
+    StorageError,
+};
+
+use crate::model::addr;
+
+fn config(chunk_size: usize, max_retries: u32) -> InsertConfig {
+    InsertConfig {
+        chunk_size,
+        max_retries,
+        initial_backoff_ms: 1,
+        max_backoff_ms: 2,
+    }
+}
+
+#[tokio::test]
+async fn test_chunks_and_progress() -> Result<()> {
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let insert = ChunkedInsert::new(config(4, 0))?.on_progress(move |p: &InsertProgress| {
+        if let Ok(mut seen) = seen.lock() {
+            seen.push((p.chunk, p.rows, p.written));
+        }
+    });
+
+    let rows: Vec<u32> = (0..10).collect();
+    let mut sizes = Vec::new();
+    let mut summary = InsertSummary::default();
+    insert
+        .run("t", &rows, &mut summary, |chunk| {
+            sizes.push(chunk.len());
+            ready(Ok(()))
+        })
+        .await?;
+    insert.run("t", &rows[..3], &mut summary, |_| ready(Ok(()))).await?;
+
+    assert_eq!(sizes, [4, 4, 2]);
+    assert_eq!(summary, InsertSummary { rows: 13, chunks: 4, retries: 0 });
+    let progress = progress.lock().map(|p| p.clone()).unwrap_or_default();
+    assert_eq!(progress, [(1, 4, 4), (2, 4, 8), (3, 2, 10), (4, 3, 13)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retries_transient_errors() -> Result<()> {
+    let insert = ChunkedInsert::new(config(2, 3))?;
+    let rows = [1, 2, 3];
+    let mut failures = 2;
+
+    let mut summary = InsertSummary::default();
+    insert
+        .run("t", &rows, &mut summary, |_| {
+            let result = if failures > 0 { Err(Error::TimedOut) } else { Ok(()) };
+            failures -= 1;
+            ready(result)
+        })
+        .await?;
+
+    assert_eq!(summary, InsertSummary { rows: 3, chunks: 2, retries: 2 });
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gives_up() -> Result<()> {
+    let insert = ChunkedInsert::new(config(2, 2))?;
+    let rows = [1, 2, 3];
+
+    let mut attempts = 0;
+    let mut summary = InsertSummary::default();
+    let result = insert
+        .run("t", &rows, &mut summary, |chunk| {
+            attempts += 1;
+            ready(if chunk[0] == 3 { Err(Error::TimedOut) } else { Ok(()) })
+        })
+        .await;
+    assert!(matches!(result, Err(StorageError::Insert { table: "t", written: 2, .. })));
+    assert_eq!(attempts, 1 + 3);
+
+    let mut attempts = 0;
+    let result = insert
+        .run("t", &rows, &mut InsertSummary::default(), |_| {
+            attempts += 1;
+            ready(Err(Error::BadResponse("Code: 60. DB::Exception: Unknown table".to_string())))
+        })
+        .await;
+    assert!(matches!(result, Err(StorageError::Insert { written: 0, .. })));
+    assert_eq!(attempts, 1);
+    Ok(())
+}
+
+#[test]
+fn test_transient_classification() {
+    let response = |reason: &str| Error::BadResponse(reason.to_string());
+
+    assert!(is_transient(&Error::TimedOut));
+    assert!(is_transient(&response("Code: 252. DB::Exception: Too many parts (300).")));
+    assert!(is_transient(&response("Code: 241. DB::Exception: Memory limit exceeded")));
+    assert!(is_transient(&response("503 Service Unavailable")));
+    assert!(!is_transient(&response("Code: 62. DB::Exception: Syntax error")));
+    assert!(!is_transient(&response("400 Bad Request")));
+    assert!(!is_transient(&Error::NotEnoughData));
+}
+
+#[test]
+fn test_backoff_doubles_up_to_max() -> Result<()> {
+    let settings = InsertConfig {
+        initial_backoff_ms: 100,
+        max_backoff_ms: 1_000,
+        ..InsertConfig::default()
+    };
+
+    let delays: Vec<_> = (0..6).map(|retry| settings.backoff(retry)).collect();
+    assert_eq!(delays, [100, 200, 400, 800, 1_000, 1_000].map(Duration::from_millis));
+    assert_eq!(settings.backoff(200), Duration::from_millis(1_000));
+
+    assert!(ChunkedInsert::new(config(0, 1)).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_memory_summary() -> Result<()> {
+    let storage = InMemoryStorage::new();
+    let stats = mycrate::model::UserStats {
+        address: addr("A"),
+        total_volume: Default::default(),
+        avg_buy_price: 0.0,
+        avg_sell_price: 0.0,
+        max_balance: Default::default(),
+        max_balance_windows: Default::default(),
+    };
+
+    assert_eq!(storage.save_stats(&[]).await?, InsertSummary::default());
+    assert_eq!(storage.save_stats(&[stats]).await?, InsertSummary::single(1));
+    Ok(())
+}
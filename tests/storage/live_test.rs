@@ -0,0 +1,117 @@
+use anyhow::Result;
+use clickhouse::{Client, Row};
+
+use mycrate::generator::generate_transfers;
+use mycrate::model::UserStats;
+use mycrate::pipeline::calculate_user_stats;
+use mycrate::storage::live::SELECT_LIVE_STATS;
+use mycrate::storage::rows::UserStatsRow;
+use mycrate::storage::{ChunkedInsert, ClickHouseStorage, Migrator, SaveMode, Storage};
+
+use crate::storage::TestDatabase;
+
+fn by_address(mut stats: Vec<UserStats>) -> Vec<UserStats> {
+    stats.sort_by_key(|s| s.address);
+    stats
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(1.0)
+}
+
+#[test]
+fn test_live_select_has_every_column() {
+    for column in UserStatsRow::COLUMN_NAMES {
+        assert!(
+            SELECT_LIVE_STATS.contains(&format!(" AS {}\n", column))
+                || SELECT_LIVE_STATS.contains(&format!(" AS {},", column)),
+            "column {} is not selected",
+            column
+        );
+    }
+    assert!(!SELECT_LIVE_STATS.contains('?'));
+}
+
+/// Needs a ClickHouse server at `CLICKHOUSE_TEST_URL` and skips without one.
+#[tokio::test]
+async fn test_live_stats_follow_inserts() -> Result<()> {
+    let Some(database) = TestDatabase::create("live").await? else {
+        return Ok(());
+    };
+
+    let result = follow_inserts(database.client.clone()).await;
+    database.drop().await?;
+    result
+}
+
+async fn follow_inserts(client: Client) -> Result<()> {
+    let migrator = Migrator::new(client.clone());
+    migrator.up(None).await?;
+    let storage =
+        ClickHouseStorage::with_options(client.clone(), ChunkedInsert::default(), SaveMode::Append)
+            .await?;
+    assert!(storage.with_live_stats().await.is_err());
+
+    let mut transfers = generate_transfers(500)?;
+    let storage =
+        ClickHouseStorage::with_options(client.clone(), ChunkedInsert::default(), SaveMode::Append)
+            .await?;
+    storage.save_transfers(&transfers).await?;
+
+    migrator.with_live_stats().up(None).await?;
+    let storage =
+        ClickHouseStorage::with_options(client.clone(), ChunkedInsert::default(), SaveMode::Append)
+            .await?
+            .with_live_stats()
+            .await?;
+
+    let more = generate_transfers(500)?;
+    storage.save_transfers(&more).await?;
+    transfers.extend(more);
+
+    let live = by_address(storage.get_stats().await?);
+    let expected = calculate_user_stats(&transfers)?;
+    assert_eq!(live.len(), expected.len());
+    for (expected, actual) in expected.iter().zip(&live) {
+        assert_eq!(expected.address, actual.address);
+        assert_eq!(expected.total_volume, actual.total_volume, "{}", actual.address);
+        assert!(close(expected.avg_buy_price, actual.avg_buy_price), "{}", actual.address);
+        assert!(close(expected.avg_sell_price, actual.avg_sell_price), "{}", actual.address);
+    }
+    Ok(())
+}
+
+/// Needs a ClickHouse server at `CLICKHOUSE_TEST_URL` and skips without one.
+#[tokio::test]
+async fn test_live_stats_ignore_saved_transfers() -> Result<()> {
+    let Some(database) = TestDatabase::create("live_twice").await? else {
+        return Ok(());
+    };
+
+    let result = save_twice(database.client.clone()).await;
+    database.drop().await?;
+    result
+}
+
+async fn save_twice(client: Client) -> Result<()> {
+    Migrator::new(client.clone()).with_live_stats().up(None).await?;
+    let storage =
+        ClickHouseStorage::with_options(client.clone(), ChunkedInsert::default(), SaveMode::Append)
+            .await?
+            .with_live_stats()
+            .await?;
+
+    let transfers = generate_transfers(300)?;
+    storage.save_transfers(&transfers).await?;
+    let once = by_address(storage.get_stats().await?);
+    let summary = storage.save_transfers(&transfers).await?;
+    let twice = by_address(storage.get_stats().await?);
+
+    assert_eq!(summary.rows, 0);
+    assert_eq!(once.len(), twice.len());
+    for (once, twice) in once.iter().zip(&twice) {
+        assert_eq!(once.address, twice.address);
+        assert_eq!(once.total_volume, twice.total_volume, "{}", twice.address);
+    }
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use tokio::sync::RwLock;
+
+use mycrate::generator::generate_transfers;
+use mycrate::model::{Amount, Transfer, UserStats};
+use mycrate::pipeline::calculate_user_stats;
+use mycrate::storage::commands::InMemorySaveStatsCommand;
+use mycrate::storage::memory::InMemoryState;
+use mycrate::storage::queries::InMemoryGetStatsQuery;
+use mycrate::storage::dedup::{transfer_keys, TransferKeys};
+use mycrate::storage::{GetStatsQuery, InMemoryStorage, SaveMode, SaveStatsCommand, Storage};
+
+use crate::model::addr;
+
+fn stats(address: &str, total_volume: i64) -> UserStats {
+    UserStats {
+        address: addr(address),
+        total_volume: Amount::from_units(total_volume),
+        avg_buy_price: 0.0,
+        avg_sell_price: 0.0,
+        max_balance: Amount::ZERO,
+        max_balance_windows: BTreeMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_empty_storage() -> Result<()> {
+    let storage = InMemoryStorage::new();
+
+    assert!(storage.get_stats().await?.is_empty());
+    assert!(storage.get_transfers().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_save_transfers_replaces_previous() -> Result<()> {
+    let storage = InMemoryStorage::with_mode(SaveMode::Replace);
+
+    storage.save_transfers(&generate_transfers(10)?).await?;
+    assert_eq!(storage.get_transfers().await?.len(), 10);
+
+    let second = generate_transfers(3)?;
+    storage.save_transfers(&second).await?;
+
+    let saved = storage.get_transfers().await?;
+    assert_eq!(saved.len(), 3);
+    assert!(saved.windows(2).all(|w| w[0].ts <= w[1].ts));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_empty_save_keeps_data() -> Result<()> {
+    let storage = InMemoryStorage::new();
+
+    storage.save_transfers(&generate_transfers(5)?).await?;
+    storage.save_stats(&[stats("A", 1)]).await?;
+
+    let no_transfers: Vec<Transfer> = vec![];
+    storage.save_transfers(&no_transfers).await?;
+    storage.save_stats(&[]).await?;
+
+    assert_eq!(storage.get_transfers().await?.len(), 5);
+    assert_eq!(storage.get_stats().await?.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stats_ordered_by_total_volume_desc() -> Result<()> {
+    let storage = InMemoryStorage::with_mode(SaveMode::Replace);
+
+    storage
+        .save_stats(&[stats("A", 5), stats("B", 50), stats("C", 0)])
+        .await?;
+    storage
+        .save_stats(&[stats("D", 1), stats("E", 10)])
+        .await?;
+
+    let saved = storage.get_stats().await?;
+    let addresses: Vec<_> = saved.iter().map(|s| s.address).collect();
+    assert_eq!(addresses, vec![addr("E"), addr("D")]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_skips_stored_transfers() -> Result<()> {
+    let storage = InMemoryStorage::with_mode(SaveMode::Append);
+    let transfers = generate_transfers(100)?;
+
+    storage.save_transfers(&transfers[..60]).await?;
+    storage.save_transfers(&transfers[40..]).await?;
+    storage.save_transfers(&transfers).await?;
+
+    assert_eq!(storage.get_transfers().await?.len(), 100);
+    Ok(())
+}
+
+#[test]
+fn test_identical_transfers_get_distinct_keys() -> Result<()> {
+    let transfers = generate_transfers(3)?;
+    let batch = [transfers[0].clone(), transfers[1].clone(), transfers[0].clone()];
+
+    let keys = transfer_keys(&batch);
+    assert_ne!(keys[0], keys[2]);
+    assert_eq!(keys[..2], transfer_keys(&transfers[..2])[..]);
+    assert_eq!(keys, transfer_keys(&batch));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_keeps_identical_transfers_across_chunks() -> Result<()> {
+    let storage = InMemoryStorage::with_mode(SaveMode::Append);
+    let transfer = generate_transfers(1)?.remove(0);
+    let chunks = || stream::iter([vec![transfer.clone()], vec![transfer.clone()]]).boxed();
+
+    storage.save_transfers_stream(chunks()).await?;
+    storage.save_transfers_stream(chunks()).await?;
+    assert_eq!(storage.get_transfers().await?.len(), 2);
+
+    let mut keys = TransferKeys::default();
+    let chunk = std::slice::from_ref(&transfer);
+    let split = [keys.next_chunk(chunk), keys.next_chunk(chunk)];
+    assert_eq!(split.concat(), transfer_keys(&[transfer.clone(), transfer]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfers_with_equal_ts_ordered_by_key() -> Result<()> {
+    let mut transfers = generate_transfers(20)?;
+    transfers.iter_mut().for_each(|t| t.ts = 1_000);
+
+    let forward = InMemoryStorage::new();
+    forward.save_transfers(&transfers).await?;
+    transfers.reverse();
+    let backward = InMemoryStorage::new();
+    backward.save_transfers(&transfers).await?;
+
+    let read_order = transfer_keys(&forward.get_transfers().await?);
+    assert_eq!(read_order, transfer_keys(&backward.get_transfers().await?));
+
+    let mut sorted = read_order.clone();
+    sorted.sort_unstable();
+    assert_eq!(read_order, sorted);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_keeps_latest_stats_per_address() -> Result<()> {
+    let storage = InMemoryStorage::with_mode(SaveMode::Append);
+
+    storage.save_stats(&[stats("A", 5), stats("B", 50)]).await?;
+    storage.save_stats(&[stats("A", 70)]).await?;
+
+    let saved = storage.get_stats().await?;
+    let volumes: Vec<_> = saved.iter().map(|s| (s.address, s.total_volume)).collect();
+    assert_eq!(
+        volumes,
+        [(addr("A"), Amount::from_units(70)), (addr("B"), Amount::from_units(50))]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shared_between_clones() -> Result<()> {
+    let storage = InMemoryStorage::new();
+    let clone = storage.clone();
+
+    clone.save_stats(&[stats("A", 1)]).await?;
+
+    assert_eq!(storage.get_stats().await?.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_analysis_flow() -> Result<()> {
+    let storage = InMemoryStorage::new();
+    let transfers = generate_transfers(200)?;
+
+    storage.save_transfers(&transfers).await?;
+    let calculated = calculate_user_stats(&storage.get_transfers().await?)
+        .context("Failed to calculate user stats")?;
+    storage.save_stats(&calculated).await?;
+
+    let saved = storage.get_stats().await?;
+    assert_eq!(saved.len(), calculated.len());
+    assert!(saved
+        .windows(2)
+        .all(|w| w[0].total_volume >= w[1].total_volume));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_storage_usable_across_tasks() -> Result<()> {
+    let storage: Arc<dyn Storage> = Arc::new(InMemoryStorage::new());
+
+    let writer = storage.clone();
+    tokio::spawn(async move { writer.save_stats(&[stats("A", 1)]).await })
+        .await
+        .context("Writer task panicked")??;
+
+    assert_eq!(storage.get_stats().await?.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_commands_and_queries_share_state() -> Result<()> {
+    let state = Arc::new(RwLock::new(InMemoryState::default()));
+    let save = InMemorySaveStatsCommand::new(state.clone(), SaveMode::Append);
+    let query = InMemoryGetStatsQuery::new(state);
+
+    save.save_stats(&[stats("A", 1), stats("B", 2)]).await?;
+
+    let saved = query.get_stats().await?;
+    assert_eq!(saved[0].address, addr("B"));
+    Ok(())
+}
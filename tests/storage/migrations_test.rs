@@ -0,0 +1,158 @@
+use anyhow::Result;
+use clickhouse::{Client, Row};
+
+use mycrate::model::{Address, Transfer};
+use mycrate::storage::migrations::{
+    check_known, has_live_stats, latest_required, latest_version, pending, required, to_revert,
+    Migration, MIGRATIONS,
+};
+use mycrate::storage::rows::{TransferRow, UserStatsRow};
+use mycrate::storage::{
+    ChunkedInsert, ClickHouseStorage, Migrator, SaveMode, Storage, StorageError,
+};
+
+use crate::storage::TestDatabase;
+
+fn versions(migrations: &[&Migration]) -> Vec<u32> {
+    migrations.iter().map(|m| m.version).collect()
+}
+
+#[test]
+fn test_migrations_are_numbered_in_order() {
+    for (i, migration) in MIGRATIONS.iter().enumerate() {
+        assert_eq!(migration.version as usize, i + 1);
+        assert!(migration.name.starts_with(&format!("{:04}_", migration.version)));
+        assert!(Migration::statements(migration.up).count() > 0, "{}", migration.name);
+        assert!(Migration::statements(migration.down).count() > 0, "{}", migration.name);
+    }
+    assert_eq!(latest_version(), MIGRATIONS.len() as u32);
+}
+
+#[test]
+fn test_schema_has_every_written_column() {
+    let defined: Vec<&str> = MIGRATIONS
+        .iter()
+        .flat_map(|m| m.up.lines())
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|word| word.trim_matches('`'))
+        .collect();
+
+    let columns = TransferRow::COLUMN_NAMES
+        .iter()
+        .chain(UserStatsRow::COLUMN_NAMES)
+        .chain(Transfer::COLUMN_NAMES);
+    for column in columns {
+        assert!(defined.contains(column), "column {} is missing from the migrations", column);
+    }
+}
+
+#[test]
+fn test_plan_up_and_down() -> Result<()> {
+    assert_eq!(versions(&pending(&[], latest_required(false), false)?), [1, 2]);
+    assert_eq!(versions(&pending(&[], latest_required(true), true)?), [1, 2, 3]);
+    assert_eq!(versions(&pending(&[1], 2, false)?), [2]);
+    assert_eq!(versions(&pending(&[], 1, false)?), [1]);
+    assert!(pending(&[1, 2], 2, false)?.is_empty());
+    assert_eq!(versions(&pending(&[1, 2], 3, true)?), [3]);
+
+    assert_eq!(versions(&to_revert(&[1, 2, 3], 0)?), [3, 2, 1]);
+    assert_eq!(versions(&to_revert(&[1, 2], 0)?), [2, 1]);
+    assert_eq!(versions(&to_revert(&[1, 2], 1)?), [2]);
+    assert!(to_revert(&[1], 1)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_live_target_needs_live_stats() {
+    assert_eq!(latest_required(false), 2);
+    assert_eq!(latest_required(true), latest_version());
+    assert!(matches!(pending(&[1, 2], 3, false), Err(StorageError::LiveStatsDisabled(3))));
+}
+
+#[test]
+fn test_live_stats_migrations() {
+    assert!(required(true).any(|m| m.live_stats));
+    assert!(required(false).all(|m| !m.live_stats));
+    assert!(has_live_stats(&[1, 2, 3]));
+    assert!(!has_live_stats(&[1, 2]));
+}
+
+#[test]
+fn test_unknown_versions_are_refused() {
+    let latest = latest_version();
+
+    assert!(check_known(&[1, 2]).is_ok());
+    assert!(matches!(
+        check_known(&[1, 2, latest + 1]),
+        Err(StorageError::UnknownSchemaVersion { found, .. }) if found == latest + 1
+    ));
+    assert!(matches!(
+        pending(&[latest + 1], latest, false),
+        Err(StorageError::UnknownSchemaVersion { .. })
+    ));
+    assert!(matches!(to_revert(&[1], latest + 5), Err(StorageError::UnknownMigration(_))));
+}
+
+/// Tables as created before migrations existed.
+const LEGACY_SCHEMA: &str = r#"
+    CREATE TABLE transfers (
+        ts UInt64,
+        `from` String,
+        `to` String,
+        amount Float64,
+        usd_price Float64
+    ) ENGINE = MergeTree() ORDER BY (ts, `from`, `to`);
+    CREATE TABLE user_stats (
+        address String,
+        total_volume Float64,
+        avg_buy_price Float64,
+        avg_sell_price Float64,
+        max_balance Float64,
+        max_balance_1h Float64,
+        max_balance_24h Float64,
+        max_balance_7d Float64
+    ) ENGINE = ReplacingMergeTree() ORDER BY address;
+    INSERT INTO transfers VALUES
+        (1700000000, '0x000000000000000000000000000000000000000a',
+         '0x000000000000000000000000000000000000000b', 2.5, 1),
+        (1700000000, '0x000000000000000000000000000000000000000a',
+         '0x000000000000000000000000000000000000000b', 2.5, 1);
+    INSERT INTO user_stats VALUES
+        ('0x000000000000000000000000000000000000000b', 5, 1, 0, 5, 2.5, 5, 5)
+"#;
+
+/// Needs a ClickHouse server at `CLICKHOUSE_TEST_URL` and skips without one.
+#[tokio::test]
+async fn test_up_adopts_legacy_tables() -> Result<()> {
+    let Some(database) = TestDatabase::create("legacy").await? else {
+        return Ok(());
+    };
+
+    let result = adopt_legacy(database.client.clone()).await;
+    database.drop().await?;
+    result
+}
+
+async fn adopt_legacy(client: Client) -> Result<()> {
+    for statement in Migration::statements(LEGACY_SCHEMA) {
+        client.query(statement).execute().await?;
+    }
+
+    assert_eq!(Migrator::new(client.clone()).up(None).await?, [1, 2]);
+    let storage =
+        ClickHouseStorage::with_options(client.clone(), ChunkedInsert::default(), SaveMode::Append)
+            .await?;
+
+    let transfers = storage.get_transfers().await?;
+    assert_eq!(transfers.len(), 2);
+    assert_eq!(transfers[0].from, "0x000000000000000000000000000000000000000a".parse::<Address>()?);
+    assert_eq!(transfers[0].amount.to_f64(), 2.5);
+
+    let stats = storage.get_stats().await?;
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].max_balance_windows.get("1h").map(|a| a.to_f64()), Some(2.5));
+
+    let legacy = client.query("EXISTS TABLE transfers_legacy").fetch_one::<u8>().await?;
+    assert_eq!(legacy, 0);
+    Ok(())
+}
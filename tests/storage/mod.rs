@@ -0,0 +1,49 @@
+#[cfg(test)]
+pub mod insert_test;
+
+#[cfg(test)]
+pub mod live_test;
+
+#[cfg(test)]
+pub mod memory_test;
+
+#[cfg(test)]
+pub mod migrations_test;
+
+#[cfg(test)]
+pub mod pushdown_test;
+
+/// Scratch database on the ClickHouse server at `CLICKHOUSE_TEST_URL`.
+#[cfg(test)]
+pub(crate) struct TestDatabase {
+    server: clickhouse::Client,
+    name: String,
+    pub client: clickhouse::Client,
+}
+
+#[cfg(test)]
+impl TestDatabase {
+    /// Creates the database, `None` when `CLICKHOUSE_TEST_URL` is not set.
+    pub async fn create(name: &str) -> anyhow::Result<Option<Self>> {
+        let Ok(url) = std::env::var("CLICKHOUSE_TEST_URL") else {
+            eprintln!("CLICKHOUSE_TEST_URL is not set, skipping");
+            return Ok(None);
+        };
+
+        let name = format!("token_transfers_{}_{}", name, std::process::id());
+        let server = clickhouse::Client::default().with_url(&url);
+        server.query(&format!("CREATE DATABASE IF NOT EXISTS {}", name)).execute().await?;
+
+        let client = server.clone().with_database(&name);
+        Ok(Some(Self { server, name, client }))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub async fn drop(self) -> anyhow::Result<()> {
+        self.server.query(&format!("DROP DATABASE {}", self.name)).execute().await?;
+        Ok(())
+    }
+}
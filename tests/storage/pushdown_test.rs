@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use clickhouse::Client;
+
+use mycrate::generator::generate_transfers;
+use mycrate::model::{Amount, Transfer, UserStats};
+use mycrate::pipeline::{
+    calculate_user_stats_with, InProcessStatsBackend, PipelineConfig, StatsBackend,
+};
+use mycrate::storage::pushdown::stats_sql;
+use mycrate::storage::{
+    ChunkedInsert, ClickHouseStatsBackend, ClickHouseStorage, InMemoryStorage, Migrator, SaveMode,
+    Storage,
+};
+
+use crate::model::addr;
+use crate::storage::TestDatabase;
+
+const BASE_TS: u64 = 1_748_174_400;
+const HOUR: u64 = 3_600;
+const DAY: u64 = 86_400;
+
+fn config() -> Result<PipelineConfig> {
+    Ok(PipelineConfig {
+        windows: vec!["1h".parse()?, "30d".parse()?],
+        ..PipelineConfig::default()
+    })
+}
+
+fn by_address(mut stats: Vec<UserStats>) -> Vec<UserStats> {
+    stats.sort_by_key(|s| s.address);
+    stats
+}
+
+/// Transfers whose windowed maxima differ from the overall one; the
+/// watermark is the last transfer.
+fn pinned_transfers() -> Vec<Transfer> {
+    let transfer = |from: &str, to: &str, units: i64, ts: u64| Transfer {
+        ts,
+        from: addr(from),
+        to: addr(to),
+        amount: Amount::from_units(units),
+        usd_price: 1.0,
+    };
+    vec![
+        transfer("M", "A", 100, BASE_TS),
+        transfer("A", "M", 90, BASE_TS + HOUR),
+        transfer("M", "A", 5, BASE_TS + 40 * DAY),
+        transfer("A", "B", 12, BASE_TS + 40 * DAY + 2 * HOUR),
+        transfer("M", "C", 1, BASE_TS + 40 * DAY + 3 * HOUR),
+    ]
+}
+
+/// `A` peaked at 100 long ago, held 15 within the last 30 days and 3 within
+/// the last hour; `B` has held 12 since its only transfer.
+fn assert_pinned(stats: &[UserStats]) -> Result<()> {
+    let expected = [("A", 100, 3, 15), ("B", 12, 12, 12)];
+    for (name, max, hour, month) in expected {
+        let s = stats
+            .iter()
+            .find(|s| s.address == addr(name))
+            .with_context(|| format!("{} stats not found", name))?;
+        assert_eq!(s.max_balance, Amount::from_units(max), "{}", name);
+        assert_eq!(s.max_balance_windows.get("1h"), Some(&Amount::from_units(hour)), "{}", name);
+        assert_eq!(s.max_balance_windows.get("30d"), Some(&Amount::from_units(month)), "{}", name);
+    }
+    Ok(())
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(1.0)
+}
+
+#[test]
+fn test_stats_sql_covers_every_window() -> Result<()> {
+    let sql = stats_sql(&config()?.windows, 42);
+
+    assert!(sql.trim_start().starts_with("INSERT INTO user_stats"));
+    assert!(sql.contains("'1h', greatest(maxIf(balance, until > if(watermark > 3600,"));
+    assert!(sql.contains("'30d', greatest(maxIf(balance, until > if(watermark > 2592000,"));
+    assert!(sql.contains("42 AS version"));
+    assert!(!sql.contains('?'), "the client would bind ? as a parameter");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_in_process_backend_saves_stats() -> Result<()> {
+    let storage = Arc::new(InMemoryStorage::with_mode(SaveMode::Replace));
+    let transfers = generate_transfers(200)?;
+    storage.save_transfers(&transfers).await?;
+
+    let config = config()?;
+    InProcessStatsBackend::new(storage.clone()).compute_stats(&config).await?;
+
+    let saved = by_address(storage.get_stats().await?);
+    let expected = calculate_user_stats_with(&transfers, &config)?;
+    assert_eq!(saved.len(), expected.len());
+    for (saved, expected) in saved.iter().zip(&expected) {
+        assert_eq!(saved.address, expected.address);
+        assert_eq!(saved.total_volume, expected.total_volume);
+        assert_eq!(saved.max_balance_windows, expected.max_balance_windows);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_in_process_backend_pins_windows() -> Result<()> {
+    let storage = Arc::new(InMemoryStorage::with_mode(SaveMode::Replace));
+    storage.save_transfers(&pinned_transfers()).await?;
+
+    InProcessStatsBackend::new(storage.clone()).compute_stats(&config()?).await?;
+    assert_pinned(&storage.get_stats().await?)
+}
+
+/// Runs both backends on the same stored transfers and compares the saved
+/// statistics. Needs a ClickHouse server at `CLICKHOUSE_TEST_URL` and skips
+/// without one.
+#[tokio::test]
+async fn test_backends_agree() -> Result<()> {
+    let Some(database) = TestDatabase::create("pushdown").await? else {
+        return Ok(());
+    };
+
+    let result = compare_backends(database.client.clone()).await;
+    database.drop().await?;
+    result
+}
+
+#[tokio::test]
+async fn test_pushdown_pins_windows() -> Result<()> {
+    let Some(database) = TestDatabase::create("pushdown_pinned").await? else {
+        return Ok(());
+    };
+
+    let result = pushdown_pinned(database.client.clone()).await;
+    database.drop().await?;
+    result
+}
+
+async fn pushdown_pinned(client: Client) -> Result<()> {
+    Migrator::new(client.clone()).up(None).await?;
+    let storage =
+        ClickHouseStorage::with_options(client.clone(), ChunkedInsert::default(), SaveMode::Replace)
+            .await?;
+    storage.save_transfers(&pinned_transfers()).await?;
+
+    ClickHouseStatsBackend::new(client, SaveMode::Replace).compute_stats(&config()?).await?;
+    assert_pinned(&storage.get_stats().await?)
+}
+
+async fn compare_backends(client: Client) -> Result<()> {
+    Migrator::new(client.clone()).up(None).await?;
+    let storage: Arc<dyn Storage> = Arc::new(
+        ClickHouseStorage::with_options(client.clone(), ChunkedInsert::default(), SaveMode::Replace)
+            .await?,
+    );
+    storage.save_transfers(&generate_transfers(2_000)?).await?;
+
+    let config = config()?;
+    InProcessStatsBackend::new(storage.clone()).compute_stats(&config).await?;
+    let in_process = by_address(storage.get_stats().await?);
+
+    ClickHouseStatsBackend::new(client, SaveMode::Replace).compute_stats(&config).await?;
+    let pushdown = by_address(storage.get_stats().await?);
+
+    assert_eq!(in_process.len(), pushdown.len());
+    for (expected, actual) in in_process.iter().zip(&pushdown) {
+        assert_eq!(expected.address, actual.address);
+        assert_eq!(expected.total_volume, actual.total_volume, "{}", actual.address);
+        assert_eq!(expected.max_balance, actual.max_balance, "{}", actual.address);
+        assert_eq!(expected.max_balance_windows, actual.max_balance_windows, "{}", actual.address);
+        assert!(close(expected.avg_buy_price, actual.avg_buy_price), "{}", actual.address);
+        assert!(close(expected.avg_sell_price, actual.avg_sell_price), "{}", actual.address);
+    }
+    Ok(())
+}